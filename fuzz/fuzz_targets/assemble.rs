@@ -0,0 +1,17 @@
+//! Feeds arbitrary source through the assembler, which should return an error rather than panic
+//! or overflow its stack.
+//!
+//! Run it from the repository root with
+//! `cargo +nightly fuzz run assemble fuzz/corpus/assemble fuzz/seeds/assemble`, which needs
+//! `cargo install cargo-fuzz`. `fuzz/seeds/assemble` holds inputs that once crashed it, and
+//! `-- -max_total_time=60` limits how long it runs.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(source) = std::str::from_utf8(data) {
+        let _ = redstone_assembler::assemble(source);
+    }
+});
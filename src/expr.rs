@@ -0,0 +1,201 @@
+//! Constant expressions in operands, such as `(1 << 3) | MASK` or `$ - table`.
+//!
+//! `LOW(expr)` and `HIGH(expr)` are the low and high nibble of the low byte of `expr`, the halves
+//! that `stl` and `sth` or `sdl` and `sdh` take.
+
+use crate::{is_expanded_label, is_identifier, local_reference, parse_number};
+
+#[derive(Clone, Copy)]
+enum Token<'a> {
+    Number(i64),
+    Symbol(&'a str),
+    Op(&'a str),
+}
+
+/// Binary operators, from lowest to highest precedence. Comparisons are 1 when they hold and 0
+/// otherwise.
+const PRECEDENCE: &[&[&str]] = &[
+    &["==", "!=", "<", "<=", ">", ">="],
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/"],
+];
+
+/// Most operands an expression may nest in one another, such as through parentheses, which keeps
+/// deeply nested expressions from overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Evaluates an expression, resolving symbols with `lookup`.
+pub fn evaluate(expr: &str, lookup: &dyn Fn(&str) -> Option<i64>) -> Result<i64, String> {
+    let tokens = tokenize(expr).ok_or_else(|| format!("has invalid expression `{expr}`"))?;
+    let mut parser = Parser {
+        expr,
+        tokens: &tokens,
+        pos: 0,
+        depth: 0,
+        lookup,
+    };
+    let value = parser.binary(0)?;
+    match parser.next() {
+        None => Ok(value),
+        Some(_) => Err(parser.invalid()),
+    }
+}
+
+fn tokenize(expr: &str) -> Option<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut rest = expr.trim_start();
+
+    while let Some(c) = rest.chars().next() {
+        let len = match c {
+            '\'' if rest[1..].starts_with('\\') => 4,
+            '\'' => 3,
+            '<' | '>' | '=' | '!' => match rest.get(..2) {
+                Some("<<" | ">>" | "<=" | ">=" | "==" | "!=") => 2,
+                _ => 1,
+            },
+            c if c.is_ascii_alphanumeric() || c == '_' => rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '@')
+                .unwrap_or(rest.len()),
+            c => c.len_utf8(),
+        };
+        let token = rest.get(..len)?;
+        tokens.push(match token {
+            "<<" | ">>" | "+" | "-" | "*" | "/" | "&" | "|" | "^" | "(" | ")" | "==" | "!="
+            | "<" | "<=" | ">" | ">=" => Token::Op(token),
+            // The current address.
+            "$" | "." => Token::Symbol(token),
+            _ if is_identifier(token)
+                || is_expanded_label(token)
+                || local_reference(token).is_some() =>
+            {
+                Token::Symbol(token)
+            }
+            _ => Token::Number(i64::try_from(parse_number(token)?).ok()?),
+        });
+        rest = rest[len..].trim_start();
+    }
+    Some(tokens)
+}
+
+struct Parser<'a, 'b> {
+    expr: &'b str,
+    tokens: &'b [Token<'a>],
+    pos: usize,
+    /// Operands being parsed, counting those that contain the one being parsed.
+    depth: usize,
+    lookup: &'b dyn Fn(&str) -> Option<i64>,
+}
+
+impl<'a> Parser<'a, '_> {
+    fn invalid(&self) -> String {
+        format!("has invalid expression `{}`", self.expr)
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        token
+    }
+
+    /// Parses operators with at least the precedence of `PRECEDENCE[level]`.
+    fn binary(&mut self, level: usize) -> Result<i64, String> {
+        let Some(ops) = PRECEDENCE.get(level) else {
+            return self.unary();
+        };
+        let mut lhs = self.binary(level + 1)?;
+        while let Some(&Token::Op(op)) = self.tokens.get(self.pos) {
+            if !ops.contains(&op) {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.binary(level + 1)?;
+            lhs = self.apply(op, lhs, rhs)?;
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<i64, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!(
+                "has an expression nested more than {MAX_DEPTH} levels deep"
+            ));
+        }
+        self.depth += 1;
+        let value = self.operand();
+        self.depth -= 1;
+        value
+    }
+
+    /// Parses a number, symbol, function call, negation or parenthesized expression.
+    fn operand(&mut self) -> Result<i64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Symbol(name)) if matches!(self.peek(), Some(Token::Op("("))) => {
+                let value = self.unary()?;
+                match name.to_lowercase().as_str() {
+                    "low" => Ok(value & 0b1111),
+                    "high" => Ok(value >> 4 & 0b1111),
+                    _ => Err(format!(
+                        "uses unknown function `{name}`, expected `LOW` or `HIGH`"
+                    )),
+                }
+            }
+            Some(Token::Symbol(name)) => {
+                (self.lookup)(name).ok_or_else(|| match local_reference(name) {
+                    Some((number, true)) => {
+                        format!("uses local label `{name}`, but no `{number}:` follows")
+                    }
+                    Some((number, false)) => {
+                        format!("uses local label `{name}`, but no `{number}:` precedes it")
+                    }
+                    None => format!("uses undefined symbol `{name}`"),
+                })
+            }
+            Some(Token::Op("-")) => self.unary()?.checked_neg().ok_or_else(|| self.overflow()),
+            Some(Token::Op("+")) => self.unary(),
+            Some(Token::Op("(")) => {
+                let value = self.binary(0)?;
+                match self.next() {
+                    Some(Token::Op(")")) => Ok(value),
+                    _ => Err(self.invalid()),
+                }
+            }
+            _ => Err(self.invalid()),
+        }
+    }
+
+    fn apply(&self, op: &str, lhs: i64, rhs: i64) -> Result<i64, String> {
+        match op {
+            "+" => lhs.checked_add(rhs),
+            "-" => lhs.checked_sub(rhs),
+            "*" => lhs.checked_mul(rhs),
+            "/" if rhs == 0 => return Err(format!("divides by zero in `{}`", self.expr)),
+            "/" => lhs.checked_div(rhs),
+            "&" => Some(lhs & rhs),
+            "|" => Some(lhs | rhs),
+            "^" => Some(lhs ^ rhs),
+            "==" => Some((lhs == rhs) as i64),
+            "!=" => Some((lhs != rhs) as i64),
+            "<" => Some((lhs < rhs) as i64),
+            "<=" => Some((lhs <= rhs) as i64),
+            ">" => Some((lhs > rhs) as i64),
+            ">=" => Some((lhs >= rhs) as i64),
+            "<<" => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shl(rhs)),
+            ">>" => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shr(rhs)),
+            _ => unreachable!("unknown operator `{op}`"),
+        }
+        .ok_or_else(|| self.overflow())
+    }
+
+    fn overflow(&self) -> String {
+        format!("overflows in `{}`", self.expr)
+    }
+}
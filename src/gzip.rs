@@ -0,0 +1,32 @@
+//! Gzip files holding data in uncompressed deflate blocks, which every reader accepts.
+
+use std::io::{self, Write};
+
+/// Writes `data` as a gzip file with no modification time.
+pub fn write_gzip(out: &mut impl Write, data: &[u8]) -> io::Result<()> {
+    // Magic, deflate, no flags, no modification time, no extra flags, unknown OS.
+    out.write_all(&[0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff])?;
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        out.write_all(&[1, 0, 0, 0xff, 0xff])?;
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.write_all(&[u8::from(last)])?;
+        out.write_all(&len.to_le_bytes())?;
+        out.write_all(&(!len).to_le_bytes())?;
+        out.write_all(block)?;
+    }
+    out.write_all(&crc32(data).to_le_bytes())?;
+    out.write_all(&(data.len() as u32).to_le_bytes())
+}
+
+/// The CRC-32 checksum of `data`, as used by gzip.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
@@ -0,0 +1,330 @@
+//! The instructions of the CPU and their encoding.
+
+use std::fmt::{self, Display, Formatter};
+
+/// Registers by their number in register fields.
+const REGISTERS: [&str; 4] = ["r0", "r1", "r2", "r3"];
+
+/// Registers by their number in the `mov` source field, which interleaves r0-r3 with the
+/// special registers selected by its low bit.
+const SPECIAL_REGISTERS: [&str; 8] = ["r0", "pc", "r1", "adr", "r2", "sp", "r3", "sr"];
+
+/// Conventional names of the general purpose registers, accepted wherever their numbered name
+/// is, after the accumulator A and the x, y and z of the register file.
+const ALIASES: [(&str, Register); 4] = [
+    ("a", Register::R0),
+    ("x", Register::R1),
+    ("y", Register::R2),
+    ("z", Register::R3),
+];
+
+/// A general purpose register, of which r0 is the accumulator A.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register {
+    R0,
+    R1,
+    R2,
+    R3,
+}
+
+impl Register {
+    const ALL: [Register; 4] = [Register::R0, Register::R1, Register::R2, Register::R3];
+
+    /// The register numbered by the low two bits of `bits`.
+    fn from_bits(bits: u8) -> Self {
+        Register::ALL[(bits & 0b11) as usize]
+    }
+
+    /// The register named `name` in any case, by number or by its alias.
+    pub fn from_name(name: &str) -> Option<Self> {
+        REGISTERS
+            .iter()
+            .position(|r| r.eq_ignore_ascii_case(name))
+            .map(|number| Register::ALL[number])
+            .or_else(|| {
+                ALIASES
+                    .iter()
+                    .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+                    .map(|&(_, reg)| reg)
+            })
+    }
+
+    pub fn name(self) -> &'static str {
+        REGISTERS[self as usize]
+    }
+}
+
+/// A register `mov` can read, numbered as in its source field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    R0,
+    Pc,
+    R1,
+    Adr,
+    R2,
+    Sp,
+    R3,
+    Sr,
+}
+
+impl Source {
+    const ALL: [Source; 8] = [
+        Source::R0,
+        Source::Pc,
+        Source::R1,
+        Source::Adr,
+        Source::R2,
+        Source::Sp,
+        Source::R3,
+        Source::Sr,
+    ];
+
+    /// The register named `name` in any case, also accepting the aliases of r0-r3.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Source::from_canonical_name(name)
+            .or_else(|| Register::from_name(name).map(|reg| Source::ALL[reg as usize * 2]))
+    }
+
+    /// The register named `name` in any case, without aliases.
+    pub(crate) fn from_canonical_name(name: &str) -> Option<Self> {
+        SPECIAL_REGISTERS
+            .iter()
+            .position(|r| r.eq_ignore_ascii_case(name))
+            .map(|number| Source::ALL[number])
+    }
+
+    pub fn name(self) -> &'static str {
+        SPECIAL_REGISTERS[self as usize]
+    }
+}
+
+/// An instruction of the CPU, with its operands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    // Arithmetic
+    Sub(Register),
+    Sbc(Register),
+    Cmp(Register),
+    Cpc(Register),
+    Add(Register),
+    Adc(Register),
+    Mul(Register),
+    Div(Register),
+
+    // Logic, where shifts and rotations are by 1-4 bits
+    Lsl(u8),
+    Rol(u8),
+    Lsr(u8),
+    Ror(u8),
+    And(Register),
+    Or(Register),
+    Xor(Register),
+    Neg,
+    Not,
+    Inc,
+    Dec,
+
+    // Store/load, at a 4-bit address
+    Lds(u8),
+    Sts(u8),
+
+    // Immediate nibble to the low or high half of A
+    Stl(u8),
+    Sth(u8),
+
+    // Immediate nibble to the low or high half of `adr`
+    Sdl(u8),
+    Sdh(u8),
+
+    // Jump to `adr`, status register:= 0b0000_NVZC
+    /// Branch if overflow set.
+    Brvs,
+    Jmp,
+    /// Branch if carry set.
+    Brcs,
+    /// Branch if carry clear.
+    Brcc,
+    /// Branch if equal.
+    Breq,
+    /// Branch if not equal.
+    Brne,
+    /// Branch if negative set.
+    Brns,
+    /// Branch if negative clear.
+    Brnc,
+
+    /// Set the status register to a nibble `NVZC`.
+    Ssr(u8),
+
+    /// Move any register into one of r0-r3.
+    Mov(Source, Register),
+}
+
+/// The operands of an instruction, as encoded into its opcode.
+enum Operand {
+    None,
+    /// A register r0-r3 in bits 3-4.
+    Src(Register),
+    /// A shift amount 1-4 in bits 3-4.
+    HalfImm(u8),
+    /// A 4-bit immediate in the low nibble.
+    Imm(u8),
+    /// A status nibble in the low nibble.
+    Status(u8),
+    /// Any register as source in bits 3-4 and 0, and r0-r3 as destination in bits 1-2.
+    Mov(Source, Register),
+}
+
+impl Operand {
+    fn bits(self) -> u8 {
+        match self {
+            Operand::None => 0,
+            Operand::Src(reg) => (reg as u8) << 3,
+            Operand::HalfImm(amount) => ((amount - 1) & 0b11) << 3,
+            Operand::Imm(value) | Operand::Status(value) => value & 0b1111,
+            Operand::Mov(src, dst) => ((src as u8 >> 1) << 3) | (dst as u8) << 1 | (src as u8 & 1),
+        }
+    }
+}
+
+impl Instruction {
+    /// The mnemonic, the opcode without operands, and the operands of the instruction.
+    #[rustfmt::skip]
+    fn parts(self) -> (&'static str, u8, Operand) {
+        use Instruction::*;
+        match self {
+            Sub(src) => ("sub", 0b000_00_000, Operand::Src(src)),
+            Sbc(src) => ("sbc", 0b000_00_001, Operand::Src(src)),
+            Cmp(src) => ("cmp", 0b000_00_010, Operand::Src(src)),
+            Cpc(src) => ("cpc", 0b000_00_011, Operand::Src(src)),
+            Add(src) => ("add", 0b000_00_100, Operand::Src(src)),
+            Adc(src) => ("adc", 0b000_00_101, Operand::Src(src)),
+            Mul(src) => ("mul", 0b000_00_110, Operand::Src(src)),
+            Div(src) => ("div", 0b000_00_111, Operand::Src(src)),
+
+            Lsl(amount) => ("lsl", 0b001_00_000, Operand::HalfImm(amount)),
+            Rol(amount) => ("rol", 0b001_00_001, Operand::HalfImm(amount)),
+            Lsr(amount) => ("lsr", 0b001_00_010, Operand::HalfImm(amount)),
+            Ror(amount) => ("ror", 0b001_00_011, Operand::HalfImm(amount)),
+            And(src) => ("and", 0b001_00_100, Operand::Src(src)),
+            Or(src) => ("or", 0b001_00_101, Operand::Src(src)),
+            Xor(src) => ("xor", 0b001_00_110, Operand::Src(src)),
+            Neg => ("neg", 0b001_00_111, Operand::None),
+            Not => ("not", 0b001_01_111, Operand::None),
+            Inc => ("inc", 0b001_10_111, Operand::None),
+            Dec => ("dec", 0b001_11_111, Operand::None),
+
+            Lds(address) => ("lds", 0b010_0_0000, Operand::Imm(address)),
+            Sts(address) => ("sts", 0b010_1_0000, Operand::Imm(address)),
+
+            Stl(value) => ("stl", 0b100_0_0000, Operand::Imm(value)),
+            Sth(value) => ("sth", 0b100_1_0000, Operand::Imm(value)),
+
+            Sdl(value) => ("sdl", 0b101_0_0000, Operand::Imm(value)),
+            Sdh(value) => ("sdh", 0b101_1_0000, Operand::Imm(value)),
+
+            Brvs => ("brvs", 0b110_00_000, Operand::None),
+            Jmp => ("jmp", 0b110_00_001, Operand::None),
+            Brcs => ("brcs", 0b110_00_010, Operand::None),
+            Brcc => ("brcc", 0b110_00_011, Operand::None),
+            Breq => ("breq", 0b110_00_100, Operand::None),
+            Brne => ("brne", 0b110_00_101, Operand::None),
+            Brns => ("brns", 0b110_00_110, Operand::None),
+            Brnc => ("brnc", 0b110_00_111, Operand::None),
+
+            Ssr(status) => ("ssr", 0b110_1_0000, Operand::Status(status)),
+
+            Mov(src, dst) => ("mov", 0b111_00_00_0, Operand::Mov(src, dst)),
+        }
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        self.parts().0
+    }
+
+    pub fn encode(self) -> u8 {
+        let (_, opcode, operand) = self.parts();
+        opcode | operand.bits()
+    }
+
+    /// Decodes an opcode, or returns `None` for the encodings no instruction uses.
+    pub fn decode(byte: u8) -> Option<Self> {
+        use Instruction::*;
+        let reg = Register::from_bits(byte >> 3);
+        let amount = (byte >> 3 & 0b11) + 1;
+        let nibble = byte & 0b1111;
+        Some(match byte >> 5 {
+            0b000 => [Sub, Sbc, Cmp, Cpc, Add, Adc, Mul, Div][(byte & 0b111) as usize](reg),
+            0b001 => match byte & 0b111 {
+                0b111 => [Neg, Not, Inc, Dec][reg as usize],
+                op @ 0..=0b011 => [Lsl, Rol, Lsr, Ror][op as usize](amount),
+                op => [And, Or, Xor][(op & 0b11) as usize](reg),
+            },
+            0b010 if byte & 0b1_0000 == 0 => Lds(nibble),
+            0b010 => Sts(nibble),
+            0b100 if byte & 0b1_0000 == 0 => Stl(nibble),
+            0b100 => Sth(nibble),
+            0b101 if byte & 0b1_0000 == 0 => Sdl(nibble),
+            0b101 => Sdh(nibble),
+            0b110 => match byte & 0b1_1111 {
+                op @ 0..=0b00_111 => [Brvs, Jmp, Brcs, Brcc, Breq, Brne, Brns, Brnc][op as usize],
+                0b1_0000.. => Ssr(nibble),
+                _ => return None,
+            },
+            0b111 => Mov(
+                Source::ALL[((byte >> 3 & 0b11) << 1 | byte & 1) as usize],
+                Register::from_bits(byte >> 1),
+            ),
+            _ => return None,
+        })
+    }
+
+    /// The fields of the encoded instruction from its most significant bit, each with its name,
+    /// width in bits and the value it encodes.
+    pub fn fields(self) -> Vec<(&'static str, u32, String)> {
+        let opcode = |width| ("opcode", width, String::new());
+        match self.parts().2 {
+            Operand::None => vec![opcode(8)],
+            Operand::Src(reg) => vec![opcode(3), ("source", 2, reg.name().to_string()), opcode(3)],
+            Operand::HalfImm(amount) => {
+                vec![opcode(3), ("amount", 2, amount.to_string()), opcode(3)]
+            }
+            Operand::Imm(value) => vec![opcode(4), ("immediate", 4, value.to_string())],
+            Operand::Status(status) => {
+                let flags: String = "NVZC"
+                    .chars()
+                    .enumerate()
+                    .filter(|&(i, _)| status & 0b1000 >> i != 0)
+                    .map(|(_, flag)| flag)
+                    .collect();
+                vec![opcode(4), ("status", 4, flags)]
+            }
+            Operand::Mov(src, dst) => vec![
+                opcode(3),
+                ("source", 2, src.name().to_string()),
+                ("destination", 2, dst.name().to_string()),
+                ("source", 1, src.name().to_string()),
+            ],
+        }
+    }
+
+    /// Whether the instruction jumps to `adr`, unconditionally or depending on the status.
+    pub fn is_branch(self) -> bool {
+        matches!(self.parts(), (_, opcode, Operand::None) if opcode >> 5 == 0b110)
+    }
+}
+
+impl Display for Instruction {
+    /// Writes the instruction as assembly, the inverse of parsing it.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let (mnemonic, _, operand) = self.parts();
+        match operand {
+            Operand::None => write!(f, "{mnemonic}"),
+            Operand::Src(reg) => write!(f, "{mnemonic} {}", reg.name()),
+            Operand::HalfImm(amount) => write!(f, "{mnemonic} {amount}"),
+            Operand::Imm(value) => write!(f, "{mnemonic} {value}"),
+            Operand::Status(status) => write!(f, "{mnemonic} 0b{status:04b}"),
+            Operand::Mov(src, dst) => write!(f, "{mnemonic} {} {}", src.name(), dst.name()),
+        }
+    }
+}
@@ -0,0 +1,1990 @@
+//! An assembler for the redstone CPU, turning programs into the bytes of its 128-byte ROM.
+
+#![allow(clippy::unusual_byte_groupings)]
+
+mod expr;
+#[cfg(feature = "minecraft")]
+mod gzip;
+mod instruction;
+#[cfg(feature = "minecraft")]
+mod nbt;
+pub mod output;
+
+pub use instruction::{Instruction, Register, Source};
+
+use itertools::Itertools;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::ops::{Range, RangeInclusive};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+pub const ROM_BYTES: usize = 128;
+
+/// What the ROM bytes that are not covered by the program are filled with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Pad {
+    /// Every byte is this value. A pad of `0` decodes to `sub r0`.
+    Byte(u8),
+    /// Every gap is filled with `halt`s that jump to themselves, after one or two `nop`s to line
+    /// them up, so a program that runs into it stops there. A gap of one or two bytes is too
+    /// short for a `halt` and only holds `nop`s, which run on into the bytes after it.
+    #[default]
+    Halt,
+}
+
+impl Pad {
+    /// Fills the bytes of `rom` at `range` with the pad.
+    pub(crate) fn fill(self, rom: &mut [u8; ROM_BYTES], range: Range<usize>) {
+        match self {
+            Pad::Byte(byte) => rom[range].fill(byte),
+            Pad::Halt => {
+                let nops = range.len() % 3;
+                rom[range.start..range.start + nops]
+                    .fill(Instruction::Mov(Source::R0, Register::R0).encode());
+                for address in (range.start + nops..range.end).step_by(3) {
+                    rom[address..address + 3]
+                        .copy_from_slice(&encode_branch(Instruction::Jmp, address as u8));
+                }
+            }
+        }
+    }
+}
+
+/// How the operands of an instruction are parsed, with the variant they are parsed into.
+#[derive(Clone, Copy)]
+enum Operands {
+    None(Instruction),
+    /// A register r0-r3.
+    Src(fn(Register) -> Instruction),
+    /// A shift amount 1-4.
+    HalfImm(fn(u8) -> Instruction),
+    /// A 4-bit immediate.
+    Imm(fn(u8) -> Instruction),
+    /// A status nibble `NVZC`.
+    Status(fn(u8) -> Instruction),
+    /// Any register as source and r0-r3 as destination.
+    Mov(fn(Source, Register) -> Instruction),
+}
+
+impl Operands {
+    /// The instruction with arbitrary operands, which tells its mnemonic.
+    fn sample(self) -> Instruction {
+        match self {
+            Operands::None(instruction) => instruction,
+            Operands::Src(instruction) => instruction(Register::R0),
+            Operands::HalfImm(instruction) => instruction(1),
+            Operands::Imm(instruction) | Operands::Status(instruction) => instruction(0),
+            Operands::Mov(instruction) => instruction(Source::R0, Register::R0),
+        }
+    }
+
+    /// The operands as listed by `--list-instructions`.
+    fn description(self) -> &'static str {
+        match self {
+            Operands::None(_) => "",
+            Operands::Src(_) => "r0-r3",
+            Operands::HalfImm(_) => "1-4",
+            Operands::Imm(_) => "0-15",
+            Operands::Status(_) => "NVZC",
+            Operands::Mov(_) => "any, r0-r3",
+        }
+    }
+}
+
+/// Every instruction of the CPU, by how its operands are parsed.
+const INSTRUCTIONS: &[Operands] = &[
+    Operands::Src(Instruction::Sub),
+    Operands::Src(Instruction::Sbc),
+    Operands::Src(Instruction::Cmp),
+    Operands::Src(Instruction::Cpc),
+    Operands::Src(Instruction::Add),
+    Operands::Src(Instruction::Adc),
+    Operands::Src(Instruction::Mul),
+    Operands::Src(Instruction::Div),
+    Operands::HalfImm(Instruction::Lsl),
+    Operands::HalfImm(Instruction::Rol),
+    Operands::HalfImm(Instruction::Lsr),
+    Operands::HalfImm(Instruction::Ror),
+    Operands::Src(Instruction::And),
+    Operands::Src(Instruction::Or),
+    Operands::Src(Instruction::Xor),
+    Operands::None(Instruction::Neg),
+    Operands::None(Instruction::Not),
+    Operands::None(Instruction::Inc),
+    Operands::None(Instruction::Dec),
+    Operands::Imm(Instruction::Lds),
+    Operands::Imm(Instruction::Sts),
+    Operands::Imm(Instruction::Stl),
+    Operands::Imm(Instruction::Sth),
+    Operands::Imm(Instruction::Sdl),
+    Operands::Imm(Instruction::Sdh),
+    Operands::None(Instruction::Brvs),
+    Operands::None(Instruction::Jmp),
+    Operands::None(Instruction::Brcs),
+    Operands::None(Instruction::Brcc),
+    Operands::None(Instruction::Breq),
+    Operands::None(Instruction::Brne),
+    Operands::None(Instruction::Brns),
+    Operands::None(Instruction::Brnc),
+    Operands::Status(Instruction::Ssr),
+    Operands::Mov(Instruction::Mov),
+];
+
+/// Mnemonics expanded by the assembler rather than encoded by `INSTRUCTIONS`, with their
+/// operands and what they expand into.
+const PSEUDO_INSTRUCTIONS: &[(&str, &str, &str)] = &[
+    ("nop", "", "mov r0 r0"),
+    ("clr", "r0-r3", "sub r0, then mov r0 into r1-r3"),
+    ("halt", "", "sdl, sdh and jmp to itself"),
+    ("hlt", "", "sdl, sdh and jmp to itself"),
+    ("ldi", "0-255", "stl and sth"),
+];
+
+/// Every mnemonic the assembler understands, also used to suggest corrections for typos.
+fn mnemonics() -> impl Iterator<Item = &'static str> {
+    INSTRUCTIONS
+        .iter()
+        .map(|operands| operands.sample().mnemonic())
+        .chain(PSEUDO_INSTRUCTIONS.iter().map(|&(mnemonic, ..)| mnemonic))
+}
+
+/// Whether `name` is a mnemonic, which like register names may be written in any case.
+fn is_mnemonic(name: &str) -> bool {
+    mnemonics().any(|mnemonic| mnemonic.eq_ignore_ascii_case(name))
+}
+
+fn register<'a>(reg: &'a str) -> Result<Register, Error<'a>> {
+    Register::from_name(reg)
+        .ok_or_else(|| Error::at(reg, format!("uses `{reg}`, which is not one of r0-r3")))
+}
+
+fn source<'a>(reg: &'a str) -> Result<Source, Error<'a>> {
+    Source::from_name(reg)
+        .ok_or_else(|| Error::at(reg, format!("uses `{reg}`, which is not a register")))
+}
+
+/// Parses the destination of `mov`. Its destination field is only the two bits left over
+/// next to the three-bit source, so the special registers can't be written at all.
+fn destination<'a>(reg: &'a str) -> Result<Register, Error<'a>> {
+    match Source::from_name(reg) {
+        Some(_) if Register::from_name(reg).is_none() => Err(Error::at(
+            reg,
+            format!("moves into `{reg}`, but `mov` can only write r0-r3"),
+        )),
+        _ => register(reg),
+    }
+}
+
+fn half_imm<'a>(half_imm: &'a str, symbols: &Symbols) -> Result<u8, Error<'a>> {
+    Ok(parse_operand(half_imm, 1..=4, "shift amount", symbols)? as u8)
+}
+
+fn imm<'a>(imm: &'a str, symbols: &Symbols) -> Result<u8, Error<'a>> {
+    parse_field(imm, 4, "immediate", symbols)
+}
+
+/// Parses a status nibble, also accepting four bare binary digits such as `1010` and the
+/// letters of the flags to set such as `n,z` or `NZ`, which take precedence over symbols.
+fn status<'a>(status: &'a str, symbols: &Symbols) -> Result<u8, Error<'a>> {
+    if status.len() == 4 && status.chars().all(|c| c == '0' || c == '1') {
+        return Ok(u8::from_str_radix(status, 2).unwrap());
+    }
+    if let Some(flags) = status_flags(status) {
+        return Ok(flags);
+    }
+    Ok(parse_operand(status, 0..=0b1111, "status", symbols)? as u8)
+}
+
+/// Packs the letters of the flags `NVZC`, in any order and case and optionally separated by
+/// commas, into a status nibble.
+fn status_flags(flags: &str) -> Option<u8> {
+    let mut status = 0;
+    for c in flags.chars().filter(|&c| c != ',' && !c.is_whitespace()) {
+        let flag = 0b1000 >> "nvzc".find(c.to_ascii_lowercase())?;
+        if status & flag != 0 {
+            return None;
+        }
+        status |= flag;
+    }
+    (status != 0).then_some(status)
+}
+
+fn branch(name: &str) -> Option<Instruction> {
+    INSTRUCTIONS.iter().find_map(|operands| match operands {
+        Operands::None(instruction)
+            if instruction.mnemonic().eq_ignore_ascii_case(name) && instruction.is_branch() =>
+        {
+            Some(*instruction)
+        }
+        _ => None,
+    })
+}
+
+/// Parses a number literal, written in binary, hex or decimal, or as a quoted character.
+pub fn parse_number(value: &str) -> Option<usize> {
+    if let Some(bin) = value.strip_prefix("0b") {
+        usize::from_str_radix(bin, 2).ok()
+    } else if let Some(hex) = value.strip_prefix("0x") {
+        usize::from_str_radix(hex, 16).ok()
+    } else if value.starts_with('\'') {
+        parse_char(value).map(usize::from)
+    } else {
+        value.parse().ok()
+    }
+}
+
+/// Evaluates an operand expression and checks that it lies in `range`.
+fn parse_operand<'a>(
+    operand: &'a str,
+    range: RangeInclusive<i64>,
+    field: &str,
+    symbols: &Symbols,
+) -> Result<i64, Error<'a>> {
+    let value = symbols.evaluate(operand)?;
+    if range.contains(&value) {
+        return Ok(value);
+    }
+    let shown = match parse_number(operand.strip_prefix('-').unwrap_or(operand)) {
+        Some(_) => format!("`{operand}`"),
+        None => format!("`{operand}` ({value})"),
+    };
+    Err(Error::at(
+        operand,
+        format!(
+            "uses {field} {shown}, which is not in {}..={}",
+            range.start(),
+            range.end()
+        ),
+    ))
+}
+
+/// Evaluates an operand for a field `bits` wide, accepting both signed and unsigned values and
+/// encoding negative ones in two's complement.
+fn parse_field<'a>(
+    operand: &'a str,
+    bits: u32,
+    field: &str,
+    symbols: &Symbols,
+) -> Result<u8, Error<'a>> {
+    let range = -(1 << (bits - 1))..=(1 << bits) - 1;
+    let value = parse_operand(operand, range, field, symbols)?;
+    Ok((value & ((1 << bits) - 1)) as u8)
+}
+
+/// The rest of a line after its first `n` whitespace-separated tokens.
+fn skip_tokens(line: &str, n: usize) -> &str {
+    let mut rest = line.trim();
+    for _ in 0..n {
+        rest = rest[rest.find(char::is_whitespace).unwrap_or(rest.len())..].trim_start();
+    }
+    rest
+}
+
+/// Comma-separated arguments following a directive.
+fn directive_args<'a>(line: &'a str, directive: &str) -> Vec<&'a str> {
+    line.trim()[directive.len()..]
+        .split(',')
+        .map(str::trim)
+        .collect()
+}
+
+/// Parses a double-quoted string literal into its character codes.
+fn parse_string(literal: &str) -> Option<Vec<u8>> {
+    let mut chars = literal.strip_prefix('"')?.strip_suffix('"')?.chars();
+    let mut bytes = Vec::new();
+    while let Some(c) = chars.next() {
+        bytes.push(match c {
+            '\\' => parse_escape(chars.next()?)?,
+            '"' => return None,
+            c if c.is_ascii() => c as u8,
+            _ => return None,
+        });
+    }
+    Some(bytes)
+}
+
+/// Parses a single-quoted character literal into its character code.
+fn parse_char(literal: &str) -> Option<u8> {
+    let mut chars = literal.strip_prefix('\'')?.strip_suffix('\'')?.chars();
+    let code = match chars.next()? {
+        '\\' => parse_escape(chars.next()?)?,
+        '\'' => return None,
+        c if c.is_ascii() => c as u8,
+        _ => return None,
+    };
+    chars.next().is_none().then_some(code)
+}
+
+/// Character code of the escape sequence `\c`.
+fn parse_escape(c: char) -> Option<u8> {
+    Some(match c {
+        'n' => b'\n',
+        't' => b'\t',
+        'r' => b'\r',
+        '0' => b'\0',
+        '\\' => b'\\',
+        '"' => b'"',
+        '\'' => b'\'',
+        _ => return None,
+    })
+}
+
+/// Constants and labels that operand expressions can refer to.
+#[derive(Default)]
+struct Symbols<'a> {
+    constants: HashMap<&'a str, i64>,
+    labels: HashMap<&'a str, usize>,
+    /// Labels that an expression or branch has referred to.
+    used: RefCell<HashSet<String>>,
+    /// Address of the first byte of the line being assembled, which `$` and `.` refer to.
+    address: usize,
+    /// Every local label with its address, in order of definition.
+    locals: Vec<(&'a str, usize)>,
+}
+
+impl Symbols<'_> {
+    fn evaluate<'a>(&self, expr: &'a str) -> Result<i64, Error<'a>> {
+        expr::evaluate(expr, &|name| match (name, local_reference(name)) {
+            ("$" | ".", _) => Some(self.address as i64),
+            (_, Some((number, forward))) => {
+                self.local(number, forward).map(|address| address as i64)
+            }
+            _ => self.constants.get(name).copied().or_else(|| {
+                let &address = self.labels.get(name)?;
+                self.used.borrow_mut().insert(name.to_string());
+                Some(address as i64)
+            }),
+        })
+        .map_err(|e| Error::at(expr, e))
+    }
+
+    /// The address of the next local label `number` after the current line, or of the previous
+    /// one. A label at the address of the line precedes it, as the labels at an address come
+    /// before the instruction there.
+    fn local(&self, number: &str, forward: bool) -> Option<usize> {
+        let mut locals = self.locals.iter().filter(|&&(local, _)| local == number);
+        let &(_, address) = if forward {
+            locals.find(|&&(_, address)| address > self.address)?
+        } else {
+            locals.rfind(|&&(_, address)| address <= self.address)?
+        };
+        Some(address)
+    }
+}
+
+/// An error message, pointing at the token of the source line it is about when known.
+struct Error<'a> {
+    message: String,
+    token: Option<&'a str>,
+    /// A hint on how to fix the error, shown below the line.
+    help: Option<String>,
+}
+
+impl<'a> Error<'a> {
+    fn at(token: &'a str, message: String) -> Self {
+        Error {
+            message,
+            token: Some(token),
+            help: None,
+        }
+    }
+}
+
+impl From<String> for Error<'_> {
+    fn from(message: String) -> Self {
+        Error {
+            message,
+            token: None,
+            help: None,
+        }
+    }
+}
+
+fn parse_instr<'a>(line: &'a str, symbols: &Symbols) -> Result<Instruction, Error<'a>> {
+    let operand = skip_tokens(line, 1);
+    // Commas between operands are optional, as in `mov r0, r1`.
+    let tokens = line
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .collect_vec();
+    match tokens.as_slice() {
+        // Pseudo-instructions
+        [nop] if nop.eq_ignore_ascii_case("nop") => {
+            return Ok(Instruction::Mov(Source::R0, Register::R0))
+        }
+        // A return address pushed with `sts` can be loaded back with `lds`, but no instruction
+        // moves a register into `adr` or `pc`, so there is no way to jump to it. Both names are
+        // left unreserved so a program can still define them as macros.
+        [name, ..] if ["call", "ret"].iter().any(|call| name.eq_ignore_ascii_case(call)) => {
+            return Err(Error::at(
+                name,
+                format!("uses `{name}`, which is unsupported because no instruction can jump to an address held in a register"),
+            ))
+        }
+        // `sp` can be read with `mov` but never written, and `lds` and `sts` only take a fixed
+        // address, so there is neither a stack to push to nor a way to move its pointer. Like
+        // `call` and `ret`, both names are left free for macros.
+        [name, ..] if ["push", "pop"].iter().any(|op| name.eq_ignore_ascii_case(op)) => {
+            return Err(Error::at(
+                name,
+                format!("uses `{name}`, which is unsupported because `sp` can't be written and `lds` and `sts` only take a fixed address"),
+            ))
+        }
+        // The branches fill the 0b110_00 block, with `jmp` where branching on overflow clear
+        // would be, and the CPU decodes no other branch, so there is no opcode for `brvc`.
+        [name, ..] if name.eq_ignore_ascii_case("brvc") => {
+            return Err(Error::at(
+                name,
+                format!("uses `{name}`, which is unsupported because `jmp` takes the opcode of branching on overflow clear"),
+            ))
+        }
+        _ => {}
+    }
+
+    let operands = INSTRUCTIONS.iter().find(|operands| {
+        tokens
+            .first()
+            .is_some_and(|name| operands.sample().mnemonic().eq_ignore_ascii_case(name))
+    });
+    let Some(&operands) = operands else {
+        return Err(invalid_instr(line));
+    };
+    Ok(match (operands, &tokens[1..]) {
+        (Operands::None(instruction), []) => instruction,
+        (Operands::Src(instruction), [src]) => instruction(register(src)?),
+        (Operands::HalfImm(instruction), [_, ..]) => instruction(half_imm(operand, symbols)?),
+        (Operands::Imm(instruction), [_, ..]) => instruction(imm(operand, symbols)?),
+        (Operands::Status(instruction), [_, ..]) => instruction(status(operand, symbols)?),
+        (Operands::Mov(instruction), [src, dst]) => instruction(source(src)?, destination(dst)?),
+        _ => return Err(invalid_instr(line)),
+    })
+}
+
+/// Explains why a line matches none of the instructions in `parse_instr`.
+fn invalid_instr(line: &str) -> Error<'_> {
+    match line.split_whitespace().next() {
+        Some(mnemonic) if is_identifier(mnemonic) && !is_mnemonic(mnemonic) => Error {
+            help: closest_mnemonic(mnemonic).map(|closest| format!("did you mean `{closest}`?")),
+            ..Error::at(
+                mnemonic,
+                format!("uses unknown instruction or macro `{mnemonic}`"),
+            )
+        },
+        _ => Error::at(
+            line.trim(),
+            format!("does not contain a valid instruction `{}`", line.trim()),
+        ),
+    }
+}
+
+/// The known mnemonic closest to `name`, if it is near enough to likely be a typo of it.
+fn closest_mnemonic(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
+    mnemonics()
+        .map(|mnemonic| (edit_distance(&name, mnemonic), mnemonic))
+        .filter(|&(distance, mnemonic)| distance <= 2 && distance < mnemonic.len().min(name.len()))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, mnemonic)| mnemonic)
+}
+
+/// Levenshtein distance between `a` and `b`, counting a swap of adjacent characters as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    // distances[i][j] is the distance between the first i bytes of `a` and first j bytes of `b`.
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// A source line after the first pass, encoded once all labels are known.
+enum Instr<'a> {
+    Instruction(&'a str),
+    /// Operand expressions of a `.byte` directive.
+    Bytes(Vec<&'a str>),
+    /// Raw bytes emitted by a data directive.
+    Data(Vec<u8>),
+    /// Branch and the expression of the address it targets, such as a label.
+    Branch(Instruction, &'a str),
+    /// A jump to its own address, which stops execution.
+    Halt,
+    /// Operand expression of an `ldi`, loaded into A with `stl` and `sth`.
+    Ldi(&'a str),
+    /// Register cleared by a `clr`.
+    Clr(Register),
+    /// A `.equ` or `.set` binding, replayed so constants have the value they had at this point.
+    Constant(&'a str, i64),
+}
+
+impl Instr<'_> {
+    fn size(&self) -> usize {
+        match self {
+            Instr::Instruction(_) => 1,
+            Instr::Bytes(values) => values.len(),
+            Instr::Data(bytes) => bytes.len(),
+            Instr::Branch(..) | Instr::Halt => 3,
+            Instr::Ldi(_) => 2,
+            Instr::Clr(reg) => encode_clr(*reg).len(),
+            Instr::Constant(..) => 0,
+        }
+    }
+}
+
+fn parse_branch(line: &str) -> Option<(Instruction, &str)> {
+    let branch = branch(line.split_whitespace().next()?)?;
+    match skip_tokens(line, 1) {
+        "" => None,
+        target => Some((branch, target)),
+    }
+}
+
+/// Clears `reg` by subtracting A from itself, which takes a single byte for A itself. Any other
+/// register is then moved the zero from A, so clearing it also clears A.
+pub fn encode_clr(reg: Register) -> Vec<u8> {
+    let mut bytes = vec![Instruction::Sub(Register::R0).encode()];
+    if reg != Register::R0 {
+        bytes.push(Instruction::Mov(Source::R0, reg).encode());
+    }
+    bytes
+}
+
+/// Branches to `address` by loading it into `adr` using `sdl` and `sdh`.
+pub fn encode_branch(branch: Instruction, address: u8) -> [u8; 3] {
+    [
+        Instruction::Sdl(address & 0b1111).encode(),
+        Instruction::Sdh(address >> 4).encode(),
+        branch.encode(),
+    ]
+}
+
+/// Loads `value` into A by setting its low nibble, then its high nibble.
+pub fn encode_ldi(value: u8) -> [u8; 2] {
+    [
+        Instruction::Stl(value & 0b1111).encode(),
+        Instruction::Sth(value >> 4).encode(),
+    ]
+}
+
+fn strip_comment(line: &str) -> &str {
+    &line[..comment_start(line).unwrap_or(line.len())]
+}
+
+/// Byte offset of the `//` starting a line comment, skipping any inside string and character
+/// literals.
+fn comment_start(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '/') if chars.peek().is_some_and(|&(_, c)| c == '/') => return Some(i),
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+/// Splits a leading `label:` off a line, returning the label and the remainder.
+fn strip_label(line: &str) -> (Option<&str>, &str) {
+    match line.split_once(':') {
+        Some((label, rest))
+            if is_identifier(label.trim())
+                || is_local_label(label.trim())
+                || is_expanded_label(label.trim()) =>
+        {
+            (Some(label.trim()), rest)
+        }
+        _ => (None, line),
+    }
+}
+
+/// Whether `name` is a number that names a local label, such as `1`, which can be defined any
+/// number of times and is referred to as `1b` or `1f`.
+fn is_local_label(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|c| c.is_ascii_digit())
+}
+
+/// Whether `name` is a label defined in a macro or `.rept` body, renamed for every expansion it
+/// is in, such as `loop@2`. No line of source can define one, as `@` is in no identifier.
+pub(crate) fn is_expanded_label(name: &str) -> bool {
+    name.split_once('@').is_some_and(|(label, expansions)| {
+        is_identifier(label) && expansions.split('@').all(is_local_label)
+    })
+}
+
+/// The name of a label as written, before any expansion renamed it.
+fn written_label(name: &str) -> &str {
+    name.split_once('@').map_or(name, |(label, _)| label)
+}
+
+/// Splits a reference to a local label such as `1b` into its number and whether it refers
+/// forward to the next definition, rather than back to the previous one.
+pub(crate) fn local_reference(name: &str) -> Option<(&str, bool)> {
+    if let Some(number) = name.strip_suffix('f') {
+        is_local_label(number).then_some((number, true))
+    } else {
+        let number = name.strip_suffix('b')?;
+        is_local_label(number).then_some((number, false))
+    }
+}
+
+/// Whether `name` can name a label, constant or macro, which also makes it a valid C identifier.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether `name` can name a constant, which excludes registers but not their aliases such as
+/// `x`, as a register operand is never an expression.
+pub fn is_constant_name(name: &str) -> bool {
+    is_identifier(name) && Source::from_canonical_name(name).is_none()
+}
+
+/// Where a source line came from, used to attribute diagnostics.
+#[derive(Clone, Debug)]
+struct Location {
+    /// The 1-based line number, as editors show it.
+    line: usize,
+    /// The included file containing the line, or `None` for the input file.
+    file: Option<Rc<Include>>,
+    /// The line as written and where the instruction starts in it without its block comments,
+    /// when the instruction is not the whole line.
+    written: Option<(Rc<WrittenLine>, usize)>,
+}
+
+/// A line as written, for a line that holds several instructions or block comments.
+#[derive(Debug)]
+struct WrittenLine {
+    text: String,
+    /// The line without its block comments.
+    stripped: String,
+    /// Where every byte of `stripped`, and its end, is in `text`.
+    offsets: Vec<usize>,
+}
+
+#[derive(Debug)]
+struct Include {
+    path: PathBuf,
+    from: Location,
+}
+
+impl Location {
+    /// The line numbers from the input file down to the line, which order lines as they appear
+    /// in the source.
+    fn position(&self) -> Vec<usize> {
+        let mut position = vec![self.line];
+        let mut file = self.file.as_deref();
+        while let Some(include) = file {
+            position.push(include.from.line);
+            file = include.from.file.as_deref();
+        }
+        position.reverse();
+        position
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Line {}", self.line)?;
+        if let Some(mut include) = self.file.as_deref() {
+            write!(
+                f,
+                " of `{}` (included from line {}",
+                include.path.display(),
+                include.from.line
+            )?;
+            while let Some(outer) = include.from.file.as_deref() {
+                write!(
+                    f,
+                    " of `{}`, included from line {}",
+                    outer.path.display(),
+                    outer.from.line
+                )?;
+                include = outer;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects the lines of a source file, read from the file `file` is included from or from the
+/// input file when `None`, with block comments removed and every instruction on its own line.
+fn load_source(
+    input: &str,
+    file: Option<Rc<Include>>,
+) -> Result<Vec<(Location, String)>, AssembleError> {
+    let mut lines = Vec::new();
+    let mut block_comment = None;
+
+    for (i, line) in input.lines().enumerate() {
+        // `lines` leaves the `\r` of a `\r\r\n` or a final lone `\r` in place.
+        let line = line.trim_end();
+        let location = Location {
+            line: i + 1,
+            file: file.clone(),
+            written: None,
+        };
+        let in_block_comment = block_comment.is_some();
+        let (stripped, offsets) = strip_block_comments(line, &mut block_comment, &location);
+        if (in_block_comment || stripped != line) && stripped.trim().is_empty() {
+            continue;
+        }
+        let instructions = split_instructions(&stripped);
+        let written = (instructions.len() > 1 || stripped != line).then(|| {
+            Rc::new(WrittenLine {
+                text: line.to_string(),
+                stripped: stripped.clone(),
+                offsets,
+            })
+        });
+        for (start, instruction) in instructions {
+            let location = Location {
+                written: written.clone().map(|written| (written, start)),
+                ..location.clone()
+            };
+            if let (Some(label), _) = strip_label(strip_comment(instruction)) {
+                if is_expanded_label(label) {
+                    let e = format!("defines label `{label}`, but only macro and `.rept` expansions may name labels with `@`");
+                    return Err(AssembleError::on_line(
+                        &location,
+                        instruction,
+                        Error::at(label, e),
+                    ));
+                }
+            }
+            lines.push((location, instruction.to_string()));
+        }
+    }
+
+    if let Some(location) = block_comment {
+        return Err(AssembleError::new(
+            &location,
+            "opens a block comment that is never closed".to_string(),
+        ));
+    }
+    Ok(lines)
+}
+
+/// Splits a line into the instructions separated by `;` outside literals and comments, leaving a
+/// comment with the last of them. Each instruction comes with where it starts in the line.
+fn split_instructions(line: &str) -> Vec<(usize, &str)> {
+    let end = comment_start(line).unwrap_or(line.len());
+    let mut instructions = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut chars = line[..end].char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, ';') => {
+                instructions.push((start, &line[start..i]));
+                start = i + 1;
+            }
+            (None, _) => {}
+        }
+    }
+    instructions.push((start, &line[start..]));
+    instructions
+}
+
+/// Removes `/* ... */` comments from a line, tracking where a comment spanning lines was opened,
+/// and returns it with where every byte of it, and its end, is in `line`. Every comment closed
+/// on the line leaves a space where it starts. Like `//`, a `/*` inside a string or character
+/// literal or a line comment is left alone.
+fn strip_block_comments(
+    line: &str,
+    block_comment: &mut Option<Location>,
+    location: &Location,
+) -> (String, Vec<usize>) {
+    let mut result = String::new();
+    let mut offsets = Vec::new();
+    let mut push = |c: char, at: usize| {
+        result.push(c);
+        offsets.extend(at..at + c.len_utf8());
+    };
+    let mut quote = None;
+    let mut comment_start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, c)| c);
+        if block_comment.is_some() {
+            if (c, next) == ('*', Some('/')) {
+                chars.next();
+                *block_comment = None;
+                push(' ', comment_start);
+            }
+            continue;
+        }
+        match (quote, c) {
+            (Some(_), '\\') => {
+                push(c, i);
+                if let Some((i, c)) = chars.next() {
+                    push(c, i);
+                }
+            }
+            (Some(q), c) if c == q => {
+                quote = None;
+                push(c, i);
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                push(c, i);
+            }
+            (None, '/') if next == Some('/') => {
+                for (i, c) in line[i..].char_indices().map(|(j, c)| (i + j, c)) {
+                    push(c, i);
+                }
+                break;
+            }
+            (None, '/') if next == Some('*') => {
+                chars.next();
+                comment_start = i;
+                *block_comment = Some(location.clone());
+            }
+            _ => push(c, i),
+        }
+    }
+    // The end of the line without a comment left open is where that comment starts.
+    offsets.push(if block_comment.is_some() {
+        comment_start
+    } else {
+        line.len()
+    });
+    (result, offsets)
+}
+
+/// A `.macro` definition.
+struct Macro {
+    params: Vec<String>,
+    body: Vec<(Location, String)>,
+}
+
+/// Comma-separated macro parameters or arguments, which may be empty.
+fn macro_args(list: &str) -> Vec<&str> {
+    match list.trim() {
+        "" => Vec::new(),
+        list => list.split(',').map(str::trim).collect(),
+    }
+}
+
+/// Replaces every `\param` in a macro body line with its argument.
+fn substitute(line: &str, params: &[String], args: &[&str]) -> String {
+    let mut result = String::new();
+    let mut rest = line;
+    while let Some((before, after)) = rest.split_once('\\') {
+        result.push_str(before);
+        let len = after
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(after.len());
+        match params.iter().position(|param| *param == after[..len]) {
+            Some(index) => {
+                result.push_str(args[index]);
+                rest = &after[len..];
+            }
+            None => {
+                result.push('\\');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Replaces every reference to a label in `renames` outside literals and comments, leaving the
+/// `\param`s of a macro body alone.
+fn rename_labels(line: &str, renames: &HashMap<&str, String>) -> String {
+    let end = comment_start(line).unwrap_or(line.len());
+    let mut result = String::new();
+    let mut rest = &line[..end];
+    let mut quote = None;
+    let identifier_len = |rest: &str| {
+        rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '@')
+            .unwrap_or(rest.len())
+    };
+    while let Some(c) = rest.chars().next() {
+        let len = match (quote, c) {
+            (Some(_), '\\') => 1 + rest[1..].chars().next().map_or(0, char::len_utf8),
+            (Some(q), c) if c == q => {
+                quote = None;
+                1
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                1
+            }
+            (None, '\\') => 1 + identifier_len(&rest[1..]),
+            (None, c) if c.is_ascii_alphanumeric() || c == '_' => {
+                let len = identifier_len(rest);
+                if let Some(name) = renames.get(&rest[..len]) {
+                    result.push_str(name);
+                    rest = &rest[len..];
+                    continue;
+                }
+                len
+            }
+            _ => c.len_utf8(),
+        };
+        result.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    result.push_str(&line[end..]);
+    result
+}
+
+/// Most lines that macros and `.rept` blocks may expand to.
+const MAX_EXPANDED_LINES: usize = 1 << 16;
+
+/// Most included files, macro invocations and `.rept` blocks that may be expanded within one
+/// another, which keeps deeply nested blocks from overflowing the stack.
+const MAX_NESTING: usize = 64;
+
+const ENDR_WITHOUT_REPT: &str = "has `.endr` without a matching `.rept`";
+
+/// Counts an expansion of `body`, naming every label it defines for this expansion so the body can
+/// be expanded more than once.
+fn rename_body_labels<'b>(
+    body: impl IntoIterator<Item = &'b str>,
+    expansions: &mut usize,
+) -> HashMap<&'b str, String> {
+    *expansions += 1;
+    let expansion = *expansions;
+    body.into_iter()
+        .filter_map(|line| strip_label(strip_comment(line)).0)
+        .filter(|label| is_identifier(label) || is_expanded_label(label))
+        .map(|label| (label, format!("{label}@{expansion}")))
+        .collect()
+}
+
+/// Takes the lines of a `.rept` block opened on line `i` from `source`, up to its `.endr`.
+fn rept_body(
+    i: &Location,
+    source: &mut dyn Iterator<Item = (Location, String)>,
+) -> Result<Vec<(Location, String)>, AssembleError> {
+    let mut body = Vec::new();
+    let mut depth = 0;
+    loop {
+        let Some((j, line)) = source.next() else {
+            return Err(AssembleError::new(
+                i,
+                "opens `.rept` without a matching `.endr`".to_string(),
+            ));
+        };
+        match strip_comment(&line).split_whitespace().next() {
+            Some(".endr") if depth == 0 => return Ok(body),
+            Some(".endr") => depth -= 1,
+            Some(".rept") => depth += 1,
+            Some(".macro") => {
+                return Err(AssembleError::new(
+                    &j,
+                    "defines a macro inside a `.rept` block".to_string(),
+                ))
+            }
+            _ => {}
+        }
+        body.push((j, line));
+    }
+}
+
+/// An open `.if` or `.ifdef` block.
+struct Condition {
+    /// The line opening the block, with its directive.
+    opened: (Location, &'static str),
+    /// Whether the lines around the block are assembled.
+    outer: bool,
+    value: bool,
+    /// Whether the block is past its `.else`.
+    in_else: bool,
+}
+
+/// Turns the source of a program into the lines the passes assemble, in order: skips the lines
+/// that `.if` and `.ifdef` leave out, splices in every `.include`d file, and replaces `.rept`
+/// blocks and macro invocations with the lines they expand to. Conditions and `.rept` counts are
+/// decided here, so they can only refer to the constants defined before them, not to labels or
+/// `$`.
+struct Expander {
+    /// The input file, which its `.include` paths are relative to.
+    path: PathBuf,
+    macros: HashMap<String, Macro>,
+    /// The macros being expanded, from the outermost.
+    active_macros: Vec<String>,
+    /// Macro invocations and `.rept` repetitions so far.
+    expansions: usize,
+    /// The files being read, from the input file, to reject recursive includes.
+    active_files: Vec<PathBuf>,
+    /// The paths of every file it tried to include.
+    files: Vec<PathBuf>,
+    /// The `.if` and `.ifdef` blocks around the line, from the outermost.
+    conditions: Vec<Condition>,
+    /// Every constant defined so far, with its value when it is known from constants alone.
+    constants: HashMap<String, Option<i64>>,
+    /// Included files, macro invocations and `.rept` blocks around the line.
+    depth: usize,
+    lines: Vec<(Location, String)>,
+}
+
+impl Expander {
+    fn new(path: &Path, defines: &[(String, i64)]) -> Self {
+        Expander {
+            path: path.to_path_buf(),
+            macros: HashMap::new(),
+            active_macros: Vec::new(),
+            expansions: 0,
+            active_files: Vec::new(),
+            files: Vec::new(),
+            conditions: Vec::new(),
+            constants: defines
+                .iter()
+                .map(|(name, value)| (name.clone(), Some(*value)))
+                .collect(),
+            depth: 0,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Expands the input file, read as `source`.
+    fn expand(&mut self, source: &str) -> Result<(), AssembleError> {
+        let path = fs::canonicalize(&self.path).unwrap_or(self.path.clone());
+        self.active_files.push(path);
+        let lines = load_source(source, None)?;
+        self.block(lines)?;
+        if let Some(Condition {
+            opened: (i, directive),
+            ..
+        }) = self.conditions.first()
+        {
+            let e = format!("opens `{directive}` without a matching `.endif`");
+            return Err(AssembleError::new(i, e));
+        }
+        Ok(())
+    }
+
+    /// Expands the lines of a file or block, taking the bodies of the blocks they open from them.
+    fn block(&mut self, lines: Vec<(Location, String)>) -> Result<(), AssembleError> {
+        let mut lines = lines.into_iter();
+        while let Some((i, line)) = lines.next() {
+            self.line(&i, &line, &mut lines)?;
+        }
+        Ok(())
+    }
+
+    /// Expands the lines of a file or block that line `i` includes, invokes or repeats.
+    fn nested(
+        &mut self,
+        i: &Location,
+        lines: Vec<(Location, String)>,
+    ) -> Result<(), AssembleError> {
+        if self.depth == MAX_NESTING {
+            return Err(AssembleError::new(
+                i,
+                format!(
+                    "nests includes, macros and `.rept` blocks more than {MAX_NESTING} levels deep"
+                ),
+            ));
+        }
+        self.depth += 1;
+        self.block(lines)?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn line(
+        &mut self,
+        i: &Location,
+        line: &str,
+        source: &mut dyn Iterator<Item = (Location, String)>,
+    ) -> Result<(), AssembleError> {
+        if self.conditional(i, line)? {
+            return Ok(());
+        }
+        let code = strip_comment(line).trim();
+        match code.split_whitespace().collect_vec().as_slice() {
+            [".macro", name, ..] => self.define_macro(i, code, name, source),
+            [".endm"] => Err(AssembleError::new(
+                i,
+                "has `.endm` without a matching `.macro`".to_string(),
+            )),
+            [".rept", ..] => {
+                let body = rept_body(i, source)?;
+                self.rept(i, line, &body)
+            }
+            [".endr"] => Err(AssembleError::new(i, ENDR_WITHOUT_REPT.to_string())),
+            [".include", ..] => self.include(i, code),
+            _ => self.invocation(i, line),
+        }
+    }
+
+    /// Defines the macro named `name` that `code` on line `i` opens, taking its body from
+    /// `source`.
+    fn define_macro(
+        &mut self,
+        i: &Location,
+        code: &str,
+        name: &str,
+        source: &mut dyn Iterator<Item = (Location, String)>,
+    ) -> Result<(), AssembleError> {
+        if !is_identifier(name) || is_mnemonic(name) {
+            return Err(AssembleError::new(
+                i,
+                format!("defines a macro with invalid name `{name}`"),
+            ));
+        }
+        let params = macro_args(&code[".macro".len()..].trim_start()[name.len()..]);
+        if let Some(param) = params.iter().find(|param| !is_identifier(param)) {
+            return Err(AssembleError::new(
+                i,
+                format!("defines macro `{name}` with invalid parameter `{param}`"),
+            ));
+        }
+        let params = params.into_iter().map(str::to_string).collect();
+        let mut body = Vec::new();
+        loop {
+            let Some((j, line)) = source.next() else {
+                return Err(AssembleError::new(
+                    i,
+                    format!("opens macro `{name}` without a matching `.endm`"),
+                ));
+            };
+            match strip_comment(&line).split_whitespace().next() {
+                Some(".endm") => break,
+                Some(".macro") => {
+                    return Err(AssembleError::new(
+                        &j,
+                        format!("defines a macro inside macro `{name}`"),
+                    ))
+                }
+                _ => body.push((j, line)),
+            }
+        }
+        if self
+            .macros
+            .insert(name.to_string(), Macro { params, body })
+            .is_some()
+        {
+            return Err(AssembleError::new(i, format!("redefines macro `{name}`")));
+        }
+        Ok(())
+    }
+
+    /// Expands `body` as many times as the `.rept count[, index]` on `line` asks, replacing every
+    /// `\index` with the number of the repetition, counting from 0.
+    fn rept(
+        &mut self,
+        i: &Location,
+        line: &str,
+        body: &[(Location, String)],
+    ) -> Result<(), AssembleError> {
+        let (count, params) = match macro_args(skip_tokens(strip_comment(line), 1)).as_slice() {
+            [count] => (*count, Vec::new()),
+            [count, index] if is_identifier(index) => (*count, vec![index.to_string()]),
+            _ => {
+                return Err(AssembleError::new(
+                    i,
+                    "has invalid `.rept` arguments".to_string(),
+                ))
+            }
+        };
+        let count = self.evaluate(count).map_err(|e| AssembleError::new(i, e))?;
+        if !(0..=ROM_BYTES as i64).contains(&count) {
+            return Err(AssembleError::new(
+                i,
+                format!("has `.rept` count {count}, which is not in 0..={ROM_BYTES}"),
+            ));
+        }
+        for index in 0..count {
+            let renames = rename_body_labels(
+                body.iter().map(|(_, line)| line.as_str()),
+                &mut self.expansions,
+            );
+            let index = index.to_string();
+            let block = body
+                .iter()
+                .map(|(j, line)| {
+                    let line = substitute(&rename_labels(line, &renames), &params, &[&index]);
+                    (j.clone(), line)
+                })
+                .collect_vec();
+            self.nested(i, block)?;
+            if self.lines.len() > MAX_EXPANDED_LINES {
+                return Err(AssembleError::new(
+                    i,
+                    format!("expands to more than {MAX_EXPANDED_LINES} lines"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Splices in the lines of the file that the `.include` in `code` on line `i` names, relative
+    /// to the file containing the line.
+    fn include(&mut self, i: &Location, code: &str) -> Result<(), AssembleError> {
+        let Some(name) = parse_string(code[".include".len()..].trim())
+            .and_then(|name| String::from_utf8(name).ok())
+        else {
+            return Err(AssembleError::new(
+                i,
+                "has an invalid `.include` path".to_string(),
+            ));
+        };
+        let path = i.file.as_ref().map_or(&self.path, |include| &include.path);
+        let included = path.parent().unwrap_or(Path::new("")).join(name);
+        let canonical = fs::canonicalize(&included).unwrap_or(included.clone());
+        if self.active_files.contains(&canonical) {
+            return Err(AssembleError::new(
+                i,
+                format!("includes `{}` recursively", included.display()),
+            ));
+        }
+        self.files.push(included.clone());
+        let input = fs::read_to_string(&included).map_err(|e| {
+            AssembleError::new(
+                i,
+                format!("could not include `{}`: {e}", included.display()),
+            )
+        })?;
+        let include = Include {
+            path: included,
+            from: i.clone(),
+        };
+        let lines = load_source(&input, Some(Rc::new(include)))?;
+        self.active_files.push(canonical);
+        self.nested(i, lines)?;
+        self.active_files.pop();
+        Ok(())
+    }
+
+    /// Replaces the macro invocation on line `i` with the lines of the macro's body, or keeps the
+    /// line when it doesn't invoke one.
+    fn invocation(&mut self, i: &Location, line: &str) -> Result<(), AssembleError> {
+        let (label, code) = strip_label(strip_comment(line));
+        let code = code.trim();
+        let Some((name, definition)) = code
+            .split_whitespace()
+            .next()
+            .and_then(|name| self.macros.get_key_value(name))
+        else {
+            self.define_constant(code);
+            self.lines.push((i.clone(), line.to_string()));
+            return Ok(());
+        };
+        let args = macro_args(&code[name.len()..]);
+        if args.len() != definition.params.len() {
+            return Err(AssembleError::new(
+                i,
+                format!(
+                    "passes {} arguments to macro `{name}`, which takes {}",
+                    args.len(),
+                    definition.params.len()
+                ),
+            ));
+        }
+        if self.active_macros.contains(name) {
+            return Err(AssembleError::new(
+                i,
+                format!("expands macro `{name}` recursively"),
+            ));
+        }
+
+        if let Some(label) = label {
+            self.lines.push((i.clone(), format!("{label}:")));
+        }
+        let renames = rename_body_labels(
+            definition.body.iter().map(|(_, line)| line.as_str()),
+            &mut self.expansions,
+        );
+        let body = definition
+            .body
+            .iter()
+            .map(|(j, line)| {
+                let line = substitute(&rename_labels(line, &renames), &definition.params, &args);
+                (j.clone(), line)
+            })
+            .collect_vec();
+        self.active_macros.push(name.clone());
+        self.nested(i, body)?;
+        self.active_macros.pop();
+        Ok(())
+    }
+
+    /// Evaluates an expression of the constants defined so far.
+    fn evaluate(&self, expr: &str) -> Result<i64, String> {
+        expr::evaluate(expr, &|name| self.constants.get(name).copied().flatten())
+    }
+
+    /// Records the constant that a `.equ` or `.set` in `instr` defines, so later conditions and
+    /// `.rept` counts can refer to it.
+    fn define_constant(&mut self, instr: &str) {
+        if let [".equ" | ".set", name, _, ..] = instr.split_whitespace().collect_vec().as_slice() {
+            let value = self.evaluate(skip_tokens(instr, 2)).ok();
+            self.constants.insert(name.to_string(), value);
+        }
+    }
+
+    /// Whether the current line is assembled, rather than skipped by a condition.
+    fn active(&self) -> bool {
+        self.conditions
+            .last()
+            .is_none_or(|condition| condition.outer && condition.value != condition.in_else)
+    }
+
+    /// Opens, flips or closes a conditional block for a `.if`, `.ifdef`, `.else` or `.endif` on
+    /// `line`, returning whether the line is one of them or skipped by one.
+    fn conditional(&mut self, i: &Location, line: &str) -> Result<bool, AssembleError> {
+        let code = strip_comment(line).trim();
+        let active = self.active();
+        let error = |e: Error| AssembleError::on_line(i, line, e);
+        let value = match code.split_whitespace().collect_vec().as_slice() {
+            [".ifdef", name] => Some((".ifdef", self.constants.contains_key(*name))),
+            [".ifdef", ..] => {
+                let e = "has invalid `.ifdef` arguments".to_string();
+                return Err(error(Error::at(code, e)));
+            }
+            // A skipped block is not evaluated, so it may use constants that aren't defined.
+            [".if", ..] if !active => Some((".if", false)),
+            [".if", ..] => {
+                let expr = skip_tokens(code, 1);
+                let value = self.evaluate(expr).map_err(|e| error(Error::at(expr, e)))?;
+                Some((".if", value != 0))
+            }
+            [".else"] => match self.conditions.last_mut() {
+                Some(condition) if !condition.in_else => {
+                    condition.in_else = true;
+                    None
+                }
+                Some(_) => {
+                    let e = "has a second `.else` in one block".to_string();
+                    return Err(error(Error::at(code, e)));
+                }
+                None => {
+                    let e = "has `.else` without a matching `.if`".to_string();
+                    return Err(error(Error::at(code, e)));
+                }
+            },
+            [".endif"] => {
+                if self.conditions.pop().is_none() {
+                    let e = "has `.endif` without a matching `.if`".to_string();
+                    return Err(error(Error::at(code, e)));
+                }
+                None
+            }
+            _ => return Ok(!active),
+        };
+        if let Some((directive, value)) = value {
+            self.conditions.push(Condition {
+                opened: (i.clone(), directive),
+                outer: active,
+                value,
+                in_else: false,
+            });
+        }
+        Ok(true)
+    }
+}
+
+/// Rejects a pseudo-instruction or branch to a label, naming the instructions to write instead.
+fn reject_pseudo(instr: &str) -> Result<(), Error<'_>> {
+    let Some(name) = instr.split_whitespace().next() else {
+        return Ok(());
+    };
+    let pseudo = PSEUDO_INSTRUCTIONS
+        .iter()
+        .find(|(mnemonic, ..)| mnemonic.eq_ignore_ascii_case(name));
+    if let Some((_, _, expansion)) = pseudo {
+        return Err(Error::at(
+            name,
+            format!("uses pseudo-instruction `{name}`, which strict mode rejects, write {expansion} instead"),
+        ));
+    }
+    if let Some((branch, target)) = parse_branch(instr) {
+        let target_name = if is_identifier(target) {
+            format!("label `{target}`")
+        } else {
+            format!("`{target}`")
+        };
+        return Err(Error::at(
+            target,
+            format!(
+                "branches to {target_name}, which strict mode rejects, load it into `adr` with sdl and sdh before `{}` instead",
+                branch.mnemonic()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Instructions, addresses and symbols collected by the first pass.
+#[derive(Default)]
+struct FirstPass<'a> {
+    /// Every instruction with its source line and address.
+    instrs: Vec<(&'a Location, &'a str, usize, Instr<'a>)>,
+    symbols: Symbols<'a>,
+    /// Constants defined with `.equ`, which may not be redefined.
+    equs: HashSet<&'a str>,
+    address: usize,
+    /// Every label with the line defining it.
+    definitions: Vec<(&'a Location, &'a str, &'a str)>,
+    /// Whether pseudo-instructions are rejected.
+    strict: bool,
+    /// Constants defined by the options, which may not be redefined.
+    defines: HashSet<&'a str>,
+}
+
+impl<'a> FirstPass<'a> {
+    /// Assigns an address to the instruction on `line` and defines its label and constants.
+    fn line(&mut self, i: &'a Location, line: &'a str) -> Result<(), Error<'a>> {
+        let (label, instr) = strip_label(strip_comment(line));
+
+        if let Some(label) = label.filter(|&label| is_local_label(label)) {
+            self.symbols.locals.push((label, self.address));
+        } else if let Some(label) = label {
+            if self.symbols.constants.contains_key(label) {
+                return Err(Error::at(
+                    label,
+                    format!("defines label `{label}`, which is already a constant"),
+                ));
+            }
+            if self.symbols.labels.insert(label, self.address).is_some() {
+                return Err(Error::at(label, format!("redefines label `{label}`")));
+            }
+            self.definitions.push((i, line, label));
+        }
+        // Blank and comment-only lines emit nothing.
+        if instr.trim().is_empty() {
+            return Ok(());
+        }
+        if self.strict {
+            reject_pseudo(instr)?;
+        }
+
+        let address = self.address;
+        let symbols = &mut self.symbols;
+        symbols.address = address;
+        let instr = match instr.split_whitespace().collect_vec().as_slice() {
+            [directive @ (".equ" | ".set"), name, _, ..] => {
+                if !is_constant_name(name) {
+                    return Err(Error::at(
+                        name,
+                        format!("defines a constant with invalid name `{name}`"),
+                    ));
+                }
+                if symbols.labels.contains_key(name) {
+                    return Err(Error::at(
+                        name,
+                        format!("defines constant `{name}`, which is already a label"),
+                    ));
+                }
+                if self.defines.contains(name) {
+                    return Err(Error::at(
+                        name,
+                        format!("redefines constant `{name}`, which is already defined with `-D`"),
+                    ));
+                }
+                if self.equs.contains(name)
+                    || *directive == ".equ" && symbols.constants.contains_key(name)
+                {
+                    return Err(Error::at(
+                        name,
+                        format!("redefines constant `{name}`, use `.set` to allow redefinition"),
+                    ));
+                }
+                let value = symbols.evaluate(skip_tokens(instr, 2))?;
+                if *directive == ".equ" {
+                    self.equs.insert(name);
+                }
+                symbols.constants.insert(name, value);
+                Instr::Constant(name, value)
+            }
+            [".org", _, ..] => {
+                let operand = skip_tokens(instr, 1);
+                let target = parse_operand(operand, 0..=i64::MAX, "address", symbols)? as usize;
+                if target >= ROM_BYTES {
+                    return Err(Error::at(
+                        operand,
+                        format!("moves the address to {target}, beyond the {ROM_BYTES}-byte ROM"),
+                    ));
+                }
+                if target < address {
+                    return Err(Error::at(
+                        operand,
+                        format!("moves the address back from {address} to {target}"),
+                    ));
+                }
+                self.address = target;
+                return Ok(());
+            }
+            [".byte", ..] => Instr::Bytes(directive_args(instr, ".byte")),
+            [directive @ (".space" | ".fill"), ..] => {
+                let (count, value) = match directive_args(instr, directive).as_slice() {
+                    [count] if *directive == ".space" => (*count, "0"),
+                    [count, value] if *directive == ".fill" => (*count, *value),
+                    _ => {
+                        return Err(Error::at(
+                            instr.trim(),
+                            format!("has invalid `{directive}` arguments"),
+                        ))
+                    }
+                };
+                let count = parse_operand(count, 0..=i64::MAX, "count", symbols)? as usize;
+                let value = parse_field(value, 8, "value", symbols)?;
+                if address + count > ROM_BYTES {
+                    return Err(format!(
+                        "fills {count} bytes from address {address}, past the {ROM_BYTES}-byte ROM"
+                    )
+                    .into());
+                }
+                Instr::Data(vec![value; count])
+            }
+            [".align", _, ..] => {
+                let value = skip_tokens(instr, 1);
+                let alignment = match symbols.evaluate(value)? {
+                    v if v > 0 && (v as usize).is_power_of_two() => v as usize,
+                    _ => {
+                        return Err(Error::at(
+                            value,
+                            format!("has `.align` argument `{value}`, which is not a power of two"),
+                        ))
+                    }
+                };
+                let target = address.next_multiple_of(alignment);
+                if target > ROM_BYTES {
+                    return Err(Error::at(
+                        value,
+                        format!("aligns the address to {target}, beyond the {ROM_BYTES}-byte ROM"),
+                    ));
+                }
+                Instr::Data(vec![0; target - address])
+            }
+            [directive @ (".ascii" | ".asciz"), ..] => {
+                let literal = skip_tokens(instr, 1);
+                let mut bytes = parse_string(literal).ok_or_else(|| {
+                    Error::at(literal, "has an invalid string literal".to_string())
+                })?;
+                if *directive == ".asciz" {
+                    bytes.push(0);
+                }
+                Instr::Data(bytes)
+            }
+            [halt] if ["halt", "hlt"].iter().any(|h| halt.eq_ignore_ascii_case(h)) => Instr::Halt,
+            [ldi, _, ..] if ldi.eq_ignore_ascii_case("ldi") => Instr::Ldi(skip_tokens(instr, 1)),
+            [clr, reg] if clr.eq_ignore_ascii_case("clr") => Instr::Clr(register(reg)?),
+            _ => match parse_branch(instr) {
+                Some((branch, label)) => Instr::Branch(branch, label),
+                None => Instr::Instruction(instr),
+            },
+        };
+        self.address += instr.size();
+        self.instrs.push((i, line, address, instr));
+        Ok(())
+    }
+}
+
+/// Warns about code right after an unconditional jump that neither a label nor any of the
+/// `jumps` points to, which can't be reached. Only the first instruction of such a run is
+/// reported, and data is not code.
+fn unreachable_code(
+    instrs: &[(&Location, &str, usize, Instr)],
+    symbols: &Symbols,
+    jumps: &HashSet<usize>,
+) -> Vec<AssembleError> {
+    let locals = symbols.locals.iter().map(|(_, address)| address);
+    let targets: HashSet<_> = symbols.labels.values().chain(locals).chain(jumps).collect();
+    let mut warnings = Vec::new();
+    let mut after_jump = false;
+    for (i, line, address, instr) in instrs {
+        if targets.contains(address) {
+            after_jump = false;
+        }
+        match instr {
+            Instr::Constant(..) => continue,
+            Instr::Bytes(_) | Instr::Data(_) => {}
+            _ if after_jump => {
+                let e = "is unreachable, as it follows an unconditional jump and has no label";
+                warnings.push(AssembleError::on_line(i, line, e.to_string().into()));
+            }
+            _ => {}
+        }
+        after_jump = match instr {
+            Instr::Halt | Instr::Branch(Instruction::Jmp, _) => true,
+            Instr::Instruction(instr) => instr.trim().eq_ignore_ascii_case("jmp"),
+            _ => false,
+        };
+    }
+    warnings
+}
+
+/// Warns about labels that nothing refers to, except those written starting with `_`.
+fn unused_labels(definitions: &[(&Location, &str, &str)], symbols: &Symbols) -> Vec<AssembleError> {
+    let used = symbols.used.borrow();
+    definitions
+        .iter()
+        .filter(|(.., label)| !written_label(label).starts_with('_') && !used.contains(*label))
+        .map(|&(i, line, token)| {
+            let label = written_label(token);
+            let e = Error {
+                help: Some(format!(
+                    "rename it to `_{label}` if it is meant to be unused"
+                )),
+                ..Error::at(
+                    token,
+                    format!("defines label `{label}`, which is never used"),
+                )
+            };
+            AssembleError::on_line(i, line, e)
+        })
+        .collect()
+}
+
+/// Encodes an instruction at `address`, replaying constant definitions into `symbols`, where
+/// `overflowed` is whether the program is reported as overflowing the ROM.
+fn encode<'a>(
+    instr: &Instr<'a>,
+    address: usize,
+    symbols: &mut Symbols<'a>,
+    overflowed: bool,
+) -> Result<Vec<u8>, Error<'a>> {
+    Ok(match *instr {
+        Instr::Instruction(instr) => vec![parse_instr(instr, symbols)?.encode()],
+        Instr::Bytes(ref values) => values
+            .iter()
+            .map(|value| parse_field(value, 8, "`.byte` value", symbols))
+            .collect::<Result<_, _>>()?,
+        Instr::Data(ref bytes) => bytes.clone(),
+        Instr::Branch(branch, target) => {
+            let address = match symbols.labels.get(target) {
+                // The labels past an overflowing program are reported with the overflow, but a
+                // label after the last byte of a full ROM is past it too.
+                Some(&address) if overflowed => {
+                    symbols.used.get_mut().insert(target.to_string());
+                    address as i64
+                }
+                None if is_identifier(target) && !symbols.constants.contains_key(target) => {
+                    return Err(Error::at(
+                        target,
+                        format!("references undefined label `{target}`"),
+                    ))
+                }
+                _ => parse_operand(target, 0..=ROM_BYTES as i64 - 1, "branch target", symbols)?,
+            };
+            encode_branch(branch, address as u8).to_vec()
+        }
+        Instr::Ldi(value) => {
+            encode_ldi(parse_operand(value, 0..=255, "immediate", symbols)? as u8).to_vec()
+        }
+        Instr::Halt => encode_branch(Instruction::Jmp, address as u8).to_vec(),
+        Instr::Clr(reg) => encode_clr(reg),
+        Instr::Constant(name, value) => {
+            symbols.constants.insert(name, value);
+            Vec::new()
+        }
+    })
+}
+
+/// An error in a program, pointing at the token of the source line it is about when known.
+#[derive(Debug)]
+pub struct AssembleError {
+    /// The 1-based line number of the error.
+    pub line: usize,
+    /// The 1-based column of the token the error is about, when known.
+    pub column: Option<usize>,
+    /// The included file containing the line, or `None` for the assembled file.
+    pub file: Option<PathBuf>,
+    pub message: String,
+    /// A hint on how to fix the error.
+    pub help: Option<String>,
+    location: Box<Location>,
+    /// The source line and the byte range of the token within it.
+    source: Option<Box<(String, Range<usize>)>>,
+}
+
+impl AssembleError {
+    fn new(location: &Location, message: String) -> Self {
+        AssembleError {
+            line: location.line,
+            column: None,
+            file: location.file.as_ref().map(|include| include.path.clone()),
+            message,
+            help: None,
+            location: Box::new(location.clone()),
+            source: None,
+        }
+    }
+
+    /// Attributes `e` to `line` at `location`, finding the token it is about within the line.
+    fn on_line(location: &Location, line: &str, e: Error) -> Self {
+        let token = e.token.and_then(|token| {
+            (token.as_ptr() as usize)
+                .checked_sub(line.as_ptr() as usize)
+                .filter(|start| start + token.len() <= line.len())
+                .map(|start| start..start + token.len())
+        });
+        // An instruction that shares its line with others or block comments is shown within the
+        // line as written, unless a macro has rewritten it.
+        let (line, token) = match &location.written {
+            Some((written, start)) if written.stripped[*start..].starts_with(line) => {
+                let offset = |i: usize| written.offsets[start + i];
+                (
+                    written.text.as_str(),
+                    token.map(|token| offset(token.start)..offset(token.end)),
+                )
+            }
+            _ => (line, token),
+        };
+        AssembleError {
+            column: token
+                .as_ref()
+                .map(|token| line[..token.start].chars().count() + 1),
+            help: e.help,
+            source: token.map(|token| Box::new((line.to_string(), token))),
+            ..AssembleError::new(location, e.message)
+        }
+    }
+}
+
+impl Display for AssembleError {
+    /// Shows the message, followed by the source line with the token underlined.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} {}.", self.location, self.message)?;
+        let help = self
+            .help
+            .as_deref()
+            .map_or(String::new(), |help| format!(" {help}"));
+        let Some((line, token)) = self.source.as_deref() else {
+            if !help.is_empty() {
+                write!(f, "\n   {help}")?;
+            }
+            return Ok(());
+        };
+        // Keep tabs so the carets line up however wide the terminal renders them.
+        let indent: String = line[..token.start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(f, "\n    {line}")?;
+        write!(
+            f,
+            "\n    {indent}{}{help}",
+            "^".repeat(line[token.clone()].chars().count().max(1))
+        )
+    }
+}
+
+impl std::error::Error for AssembleError {}
+
+/// An assembled program.
+pub struct Program {
+    pub rom: [u8; ROM_BYTES],
+    /// The address and bytes of every source line that emitted any, alongside the line.
+    pub listing: Vec<(usize, Vec<u8>, String)>,
+    /// Every instruction emitted, in order, with pseudo-instructions expanded and data left out.
+    pub instructions: Vec<Instruction>,
+    /// Every label with its address, in order of address.
+    pub labels: Vec<(String, usize)>,
+    /// Every constant with its final value, in order of name.
+    pub constants: Vec<(String, i64)>,
+    /// Problems that don't stop the program from assembling, such as unreachable code.
+    pub warnings: Vec<AssembleError>,
+}
+
+/// How a program is assembled.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Report only the first error in the source instead of every one.
+    pub fail_fast: bool,
+    /// Reject pseudo-instructions and branches to labels, allowing only real instructions.
+    pub strict: bool,
+    /// What the unused bytes of the ROM are filled with.
+    pub pad: Pad,
+    /// Constants defined before the first line, which the program can't redefine.
+    pub defines: Vec<(String, i64)>,
+}
+
+/// Assembles `source` into a ROM, returning the first error in the source if any.
+pub fn assemble(source: &str) -> Result<[u8; ROM_BYTES], AssembleError> {
+    let options = Options {
+        fail_fast: true,
+        ..Options::default()
+    };
+    match assemble_file(Path::new(""), source, options) {
+        Ok(program) => Ok(program.rom),
+        Err(mut errors) => Err(errors.remove(0)),
+    }
+}
+
+/// The files `source`, read from the file at `path`, is assembled from with the constants
+/// `defines` defined, starting with `path` itself. This includes files up to the first one that
+/// couldn't be included.
+pub fn source_files(path: &Path, source: &str, defines: &[(String, i64)]) -> Vec<PathBuf> {
+    let mut expander = Expander::new(path, defines);
+    let _ = expander.expand(source);
+    let files = expander.files.into_iter();
+    [path.to_path_buf()]
+        .into_iter()
+        .chain(files)
+        .unique()
+        .collect()
+}
+
+/// Assembles `source`, read from the file at `path` that `.include` paths are relative to,
+/// collecting every error unless `options` ask to fail fast.
+pub fn assemble_file(
+    path: &Path,
+    source: &str,
+    options: Options,
+) -> Result<Program, Vec<AssembleError>> {
+    let mut expander = Expander::new(path, &options.defines);
+    expander.expand(source).map_err(|e| vec![e])?;
+    let lines = expander.lines;
+
+    // First pass: assign an address to every instruction and label.
+    let mut errors = Vec::new();
+    let defines = options
+        .defines
+        .iter()
+        .map(|(name, value)| (name.as_str(), *value))
+        .collect_vec();
+    let mut first_pass = FirstPass {
+        strict: options.strict,
+        defines: defines.iter().map(|&(name, _)| name).collect(),
+        ..FirstPass::default()
+    };
+    first_pass.symbols.constants.extend(defines.iter().copied());
+    for (i, line) in &lines {
+        if let Err(e) = first_pass.line(i, line) {
+            errors.push(AssembleError::on_line(i, line, e));
+        }
+    }
+    let overflow = first_pass
+        .instrs
+        .iter()
+        .find(|(_, _, address, instr)| address + instr.size() > ROM_BYTES);
+    if let Some((i, line, ..)) = overflow {
+        let over = first_pass.address - ROM_BYTES;
+        let e = format!(
+            "does not fit in the {ROM_BYTES}-byte ROM, which the program exceeds by {over} byte{}",
+            if over == 1 { "" } else { "s" }
+        );
+        errors.push(AssembleError::on_line(i, line, e.into()));
+    }
+    let overflowed = overflow.is_some();
+
+    // Second pass: encode every instruction with all labels known.
+    let FirstPass {
+        instrs,
+        mut symbols,
+        definitions,
+        ..
+    } = first_pass;
+    let mut rom = [0; ROM_BYTES];
+    let mut covered = [false; ROM_BYTES];
+    let mut listing = Vec::new();
+    let mut instructions = Vec::new();
+    // Every address a branch jumps to, with the nibbles of `adr` set by the code before it.
+    let mut jumps = HashSet::new();
+    let mut adr = [None; 2];
+    symbols.constants.clear();
+    symbols.constants.extend(defines);
+
+    for &(i, line, address, ref instr) in &instrs {
+        let is_code = !matches!(instr, Instr::Bytes(_) | Instr::Data(_));
+        symbols.address = address;
+        match encode(instr, address, &mut symbols, overflowed) {
+            Ok(bytes) => {
+                if is_code {
+                    for instruction in bytes.iter().filter_map(|&byte| Instruction::decode(byte)) {
+                        match instruction {
+                            Instruction::Sdl(low) => adr[0] = Some(low),
+                            Instruction::Sdh(high) => adr[1] = Some(high),
+                            // A `halt` only jumps to itself, which doesn't make it reachable.
+                            _ if instruction.is_branch() && !matches!(instr, Instr::Halt) => {
+                                if let [Some(low), Some(high)] = adr {
+                                    jumps.insert(usize::from(high << 4 | low));
+                                }
+                            }
+                            _ => {}
+                        }
+                        instructions.push(instruction);
+                    }
+                }
+                // Bytes past the end of the ROM have already been reported as an overflow.
+                if let Some(rom) = rom.get_mut(address..address + bytes.len()) {
+                    rom.copy_from_slice(&bytes);
+                    covered[address..address + bytes.len()].fill(true);
+                }
+                if !bytes.is_empty() {
+                    listing.push((address, bytes, line.to_string()));
+                }
+            }
+            Err(e) => errors.push(AssembleError::on_line(i, line, e)),
+        }
+    }
+    if !errors.is_empty() {
+        // The passes find errors out of order, so they are reported in the order of the source.
+        errors.sort_by_key(|e| e.location.position());
+        if options.fail_fast {
+            errors.truncate(1);
+        }
+        return Err(errors);
+    }
+    let mut start = 0;
+    while let Some(gap) = covered[start..].iter().position(|&covered| !covered) {
+        let gap = start + gap;
+        start = covered[gap..]
+            .iter()
+            .position(|&covered| covered)
+            .map_or(ROM_BYTES, |end| gap + end);
+        options.pad.fill(&mut rom, gap..start);
+    }
+    let mut warnings = unreachable_code(&instrs, &symbols, &jumps);
+    warnings.extend(unused_labels(&definitions, &symbols));
+    let labels = symbols
+        .labels
+        .iter()
+        .map(|(&name, &address)| (name.to_string(), address))
+        .sorted_by(|(a_name, a), (b_name, b)| (a, a_name).cmp(&(b, b_name)))
+        .collect();
+    let constants = symbols
+        .constants
+        .iter()
+        .map(|(&name, &value)| (name.to_string(), value))
+        .sorted()
+        .collect();
+    Ok(Program {
+        rom,
+        listing,
+        instructions,
+        labels,
+        constants,
+        warnings,
+    })
+}
@@ -1,227 +1,1089 @@
-#![allow(clippy::unusual_byte_groupings)]
-
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser, ValueEnum};
 use itertools::Itertools;
-use regex::Regex;
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use redstone_assembler::output::{
+    write_c_array, write_disassembly, write_explanation, write_hexdump, write_ihex,
+    write_instruction_set, write_listing, write_litematic, write_logisim, write_map,
+    write_mcfunction, write_patch, write_preview, write_readmemh, write_schem, write_stats,
+    write_structure, write_verify, Layout,
+};
+use redstone_assembler::{
+    assemble_file, is_constant_name, is_identifier, parse_number, source_files, AssembleError,
+    Instruction, Options, Pad, Program, ROM_BYTES,
+};
+use std::collections::HashSet;
+use std::env;
 use std::fs;
 use std::fs::File;
-use std::io::Write;
-
-const ROM_BYTES: usize = 128;
-
-fn src_to_bits(reg: &str) -> Option<u8> {
-    Some(match reg {
-        "r0" => 0b000_00_000,
-        "r1" => 0b000_01_000,
-        "r2" => 0b000_10_000,
-        "r3" => 0b000_11_000,
-        _ => return None,
-    })
-}
-
-fn special_to_bits(reg: &str) -> Option<u8> {
-    Some(match reg {
-        "r0" => 0b000_00_000,
-        "r1" => 0b000_01_000,
-        "r2" => 0b000_10_000,
-        "r3" => 0b000_11_000,
-        "pc" => 0b000_00_001,
-        "adr" => 0b000_01_001,
-        "sp" => 0b000_10_001,
-        "sr" => 0b000_11_001,
-        _ => return None,
-    })
-}
-
-fn half_imm_to_bits(reg: &str) -> Option<u8> {
-    Some(match reg {
-        "1" => 0b000_00_000,
-        "2" => 0b000_01_000,
-        "3" => 0b000_10_000,
-        "4" => 0b000_11_000,
-        _ => return None,
-    })
-}
-
-fn imm_to_bits(reg: &str) -> Option<u8> {
-    Some(match reg {
-        "0" | "0b0000" => 0b0000_0000,
-        "1" | "0b0001" => 0b0000_0001,
-        "2" | "0b0010" => 0b0000_0010,
-        "3" | "0b0011" => 0b0000_0011,
-        "4" | "0b0100" => 0b0000_0100,
-        "5" | "0b0101" => 0b0000_0101,
-        "6" | "0b0110" => 0b0000_0110,
-        "7" | "0b0111" => 0b0000_0111,
-        "8" | "0b1000" => 0b0000_1000,
-        "9" | "0b1001" => 0b0000_1001,
-        "10" | "0b1010" => 0b0000_1010,
-        "11" | "0b1011" => 0b0000_1011,
-        "12" | "0b1100" => 0b0000_1100,
-        "13" | "0b1101" => 0b0000_1101,
-        "14" | "0b1110" => 0b0000_1110,
-        "15" | "0b1111" => 0b0000_1111,
-        _ => return None,
-    })
-}
-
-fn status_to_bits(reg: &str) -> Option<u8> {
-    Some(match reg {
-        "0000" => 0b0000_0000,
-        "0001" => 0b0000_0001,
-        "0010" => 0b0000_0010,
-        "0011" => 0b0000_0011,
-        "0100" => 0b0000_0100,
-        "0101" => 0b0000_0101,
-        "0110" => 0b0000_0110,
-        "0111" => 0b0000_0111,
-        "1000" => 0b0000_1000,
-        "1001" => 0b0000_1001,
-        "1010" => 0b0000_1010,
-        "1011" => 0b0000_1011,
-        "1100" => 0b0000_1100,
-        "1101" => 0b0000_1101,
-        "1110" => 0b0000_1110,
-        "1111" => 0b0000_1111,
-        _ => return None,
-    })
-}
-
-fn dst_to_bits(reg: &str) -> Option<u8> {
-    Some(match reg {
-        "r0" => 0b00000_00_0,
-        "r1" => 0b00000_01_0,
-        "r2" => 0b00000_10_0,
-        "r3" => 0b00000_11_0,
-        _ => return None,
-    })
-}
-
-fn parse_instr(line: &str) -> Option<u8> {
-    Some(match line.split_whitespace().collect_vec().as_slice() {
-        // Arithmetic
-        #[allow(clippy::identity_op)]
-        ["sub", src] => 0b000_00_000 | src_to_bits(src)?,
-        ["sbc", src] => 0b000_00_001 | src_to_bits(src)?,
-        ["cmp", src] => 0b000_00_010 | src_to_bits(src)?,
-        ["cpc", src] => 0b000_00_011 | src_to_bits(src)?,
-        ["add", src] => 0b000_00_100 | src_to_bits(src)?,
-        ["adc", src] => 0b000_00_101 | src_to_bits(src)?,
-        ["mul", src] => 0b000_00_110 | src_to_bits(src)?,
-        ["div", src] => 0b000_00_111 | src_to_bits(src)?,
-
-        // Logic
-        ["lsl", half_imm] => 0b001_00_000 | half_imm_to_bits(half_imm)?,
-        ["rol", half_imm] => 0b001_00_001 | half_imm_to_bits(half_imm)?,
-        ["lsr", half_imm] => 0b001_00_010 | half_imm_to_bits(half_imm)?,
-        ["ror", half_imm] => 0b001_00_011 | half_imm_to_bits(half_imm)?,
-        ["and", reg] => 0b001_00_100 | src_to_bits(reg)?,
-        ["or", reg] => 0b001_00_101 | src_to_bits(reg)?,
-        ["xor", reg] => 0b001_00_110 | src_to_bits(reg)?,
-
-        ["neg"] => 0b001_00_111,
-        ["not"] => 0b001_01_111,
-        ["inc"] => 0b001_10_111,
-        ["dec"] => 0b001_11_111,
-
-        // Store/load
-        ["lds", imm] => 0b010_0_0000 | imm_to_bits(imm)?,
-        ["sts", imm] => 0b010_1_0000 | imm_to_bits(imm)?,
-
-        // Special ---
-
-        // Immediate to A
-        ["stl", imm] => 0b100_0_0000 | imm_to_bits(imm)?,
-        ["sth", imm] => 0b100_1_0000 | imm_to_bits(imm)?,
-
-        // Immediate to addr
-        ["sdl", imm] => 0b101_0_0000 | imm_to_bits(imm)?,
-        ["sdh", imm] => 0b101_1_0000 | imm_to_bits(imm)?,
-
-        // Jump, status register:= 0b0000_NVZC
-        ["brvs"] => 0b110_00_000, // overflow set
-        ["jmp"] => 0b110_00_001,
-        ["brcs"] => 0b110_00_010, // carry set
-        ["brcc"] => 0b110_00_011, // carry clear
-        ["breq"] => 0b110_00_100, // eq
-        ["brne"] => 0b110_00_101, // ne
-        ["brns"] => 0b110_00_110, // negative set
-        ["brnc"] => 0b110_00_111, // negative clear
-
-        ["ssr", status] => 0b_110_1_0000 | status_to_bits(status)?,
-
-        ["mov", src, dst] => 0b111_00_00_0 | special_to_bits(src)? | dst_to_bits(dst)?,
-
-        _ => return None,
-    })
-}
-
-const SIZE_X: isize = 32;
-const STRIDE_X: isize = -2;
-const OFFSET_X: isize = -2;
-const SIZE_Y: isize = 4;
-const STRIDE_Y: isize = 4;
-const OFFSET_Y: isize = -15;
-const STRIDE_Z: isize = -2;
-const OFFSET_Z: isize = 0;
-
-fn write_byte(x: isize, y: isize, b: u8) -> String {
-    (0..8)
-        .map(|z| z * STRIDE_Z + OFFSET_Z)
-        .zip((0..8).rev().map(|m| (b >> m) & 1 != 0))
-        .map(|(z, set)| {
-            if set {
-                format!(
-                    "setblock ~{x} ~{y} ~{z} minecraft:redstone_wall_torch[facing=east] replace\n"
-                )
-            } else {
-                format!("setblock ~{x} ~{y} ~{z} minecraft:air replace\n")
-            }
-        })
+use std::io::{self, BufWriter, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, ValueEnum)]
+enum Format {
+    /// Minecraft function that places the ROM in the world.
+    Mcfunction,
+    /// Raw ROM bytes.
+    Bin,
+    /// Intel HEX records, as read by EEPROM programmers and emulators.
+    Ihex,
+    /// Rows of 16 hex bytes with their address and an ASCII gutter, for reading by eye.
+    Hexdump,
+    /// `v2.0 raw` memory image for Logisim Evolution.
+    Logisim,
+    /// One hex byte per line, for initializing memory with Verilog's `$readmemh`.
+    Readmemh,
+    /// C header defining the ROM as a `uint8_t` array.
+    CArray,
+    /// Sponge schematic of the torches, for pasting the ROM with WorldEdit.
+    Schem,
+    /// Litematica schematic of the torches.
+    Litematic,
+    /// Structure of the torches, for loading the ROM with a structure block.
+    Structure,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Mcfunction => "mcfunction",
+            Format::Bin => "bin",
+            Format::Ihex => "hex",
+            Format::Hexdump => "txt",
+            Format::Logisim => "logisim",
+            Format::Readmemh => "mem",
+            Format::CArray => "h",
+            Format::Schem => "schem",
+            Format::Litematic => "litematic",
+            Format::Structure => "nbt",
+        }
+    }
+
+    /// Writes the ROM in this format, where `array_name` names the array of `c-array` and
+    /// `layout` places the blocks of the Minecraft formats.
+    fn write(
+        self,
+        out: &mut impl Write,
+        opcodes: &[u8; ROM_BYTES],
+        array_name: &str,
+        layout: &Layout,
+    ) -> io::Result<()> {
+        match self {
+            Format::Mcfunction => write_mcfunction(out, opcodes, layout),
+            Format::Bin => out.write_all(opcodes),
+            Format::Ihex => write_ihex(out, opcodes),
+            Format::Hexdump => write_hexdump(out, opcodes),
+            Format::Logisim => write_logisim(out, opcodes),
+            Format::Readmemh => write_readmemh(out, opcodes),
+            Format::CArray => write_c_array(out, opcodes, array_name),
+            Format::Schem => write_schem(out, opcodes, layout),
+            Format::Litematic => write_litematic(out, opcodes, layout),
+            Format::Structure => write_structure(out, opcodes, layout),
+        }
+    }
+}
+
+/// Creates the file at `path` and its missing directories, and fills it with `write`.
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut out = BufWriter::new(File::create(path)?);
+    write(&mut out)?;
+    out.flush()
+}
+
+/// Derives a function name from the stem of `path`, replacing characters functions can't use.
+fn function_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map_or("out".into(), |stem| stem.to_string_lossy());
+    stem.chars()
+        .map(|c| c.to_ascii_lowercase())
+        .map(|c| if is_function_name_char(c) { c } else { '_' })
         .collect()
 }
 
+fn is_function_name_char(c: char) -> bool {
+    matches!(c, 'a'..='z' | '0'..='9' | '_' | '-' | '.')
+}
+
+fn is_function_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(is_function_name_char)
+}
+
+/// Writes the `pack.mcmeta` describing a datapack.
+fn write_pack_mcmeta(out: &mut impl Write, pack_format: u32, namespace: &str) -> io::Result<()> {
+    writeln!(out, "{{")?;
+    writeln!(out, "  \"pack\": {{")?;
+    writeln!(out, "    \"pack_format\": {pack_format},")?;
+    writeln!(
+        out,
+        "    \"description\": \"ROM functions in namespace {namespace}\""
+    )?;
+    writeln!(out, "  }}")?;
+    writeln!(out, "}}")
+}
+
+/// Reads the file at `input`, or standard input for `-`.
+fn read_input(input: &str) -> io::Result<Vec<u8>> {
+    if input == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        Ok(bytes)
+    } else {
+        fs::read(input)
+    }
+}
+
+fn parse_byte(value: &str) -> Result<u8, String> {
+    parse_number(value)
+        .and_then(|number| u8::try_from(number).ok())
+        .ok_or_else(|| format!("`{value}` is not a byte, such as `0xC4` or `0b11000100`"))
+}
+
+fn parse_pad(value: &str) -> Result<Pad, String> {
+    if ["halt", "hlt"].contains(&value.trim().to_lowercase().as_str()) {
+        return Ok(Pad::Halt);
+    }
+    if parse_number(value).is_some() {
+        return parse_byte(value).map(Pad::Byte);
+    }
+    let options = Options {
+        fail_fast: true,
+        ..Options::default()
+    };
+    match assemble_file(Path::new(""), value, options).map(|program| program.listing) {
+        Ok(listing) if listing.len() == 1 && listing[0].1.len() == 1 => {
+            Ok(Pad::Byte(listing[0].1[0]))
+        }
+        _ => Err(format!(
+            "`{value}` is not `halt`, a byte or an instruction of one byte, such as `0xE0` or `nop`"
+        )),
+    }
+}
+
+fn parse_define(define: &str) -> Result<(String, i64), String> {
+    let (name, value) = define.split_once('=').unwrap_or((define, "1"));
+    if !is_constant_name(name) {
+        return Err(format!("`{name}` is not a valid constant name"));
+    }
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value),
+    };
+    let value = parse_number(digits)
+        .and_then(|number| i64::try_from(number).ok())
+        .map(|number| if negative { -number } else { number })
+        .ok_or_else(|| format!("`{value}` is not a number, such as `3`, `-1` or `0x10`"))?;
+    Ok((name.to_string(), value))
+}
+
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, allow_negative_numbers = true)]
 struct Args {
-    /// Input file to use.
+    /// Input file to use, or `-` for standard input, which is also read when this is omitted
+    /// and standard input is not a terminal.
     #[arg(short, long)]
-    input: String,
+    input: Option<String>,
 
-    /// Output file name (without .mcfunction).
+    /// Output file name, without extension, or `-` for standard output.
     #[arg(short, long)]
     output: Option<String>,
+
+    /// Format to write the assembled ROM in, repeatable to write one file per format.
+    #[arg(short, long, value_enum, default_values_t = [Format::Mcfunction])]
+    format: Vec<Format>,
+
+    /// Directory to write the output to, such as a datapack's `data/<namespace>/functions`.
+    #[arg(long, default_value = ".")]
+    out_dir: PathBuf,
+
+    /// Name of the array in the `c-array` format.
+    #[arg(long, default_value = "rom")]
+    array_name: String,
+
+    /// Also write a listing of every address, opcode and source line to this file.
+    #[arg(long)]
+    listing: Option<PathBuf>,
+
+    /// Also write the address of every label and the value of every constant to this file.
+    #[arg(long)]
+    map: Option<PathBuf>,
+
+    /// Also print how often every instruction and category of instructions is used.
+    #[arg(long, conflicts_with = "disassemble")]
+    stats: bool,
+
+    /// Print the torches of the ROM as placed by the layout, `#` for set and `.` for clear bits,
+    /// from the top layer down, instead of writing any files.
+    #[arg(long, conflicts_with = "disassemble")]
+    preview: bool,
+
+    /// Disassemble the input ROM to standard output instead of assembling it.
+    #[arg(long)]
+    disassemble: bool,
+
+    /// Print every instruction with its operands and opcode, then exit.
+    #[arg(long)]
+    list_instructions: bool,
+
+    /// Decode one opcode byte, such as `0xC4`, and print its instruction and fields, then exit.
+    #[arg(long, value_name = "BYTE", value_parser = parse_byte)]
+    explain: Option<u8>,
+
+    /// Assemble one line, such as `add r1`, and print every byte it assembles into with its
+    /// fields, then exit.
+    #[arg(long, value_name = "LINE")]
+    eval: Option<String>,
+
+    /// Make the output directory a datapack, writing its `pack.mcmeta` and the functions into
+    /// `data/<namespace>/functions`.
+    #[arg(long)]
+    init_datapack: bool,
+
+    /// Namespace of the functions in the datapack.
+    #[arg(long, default_value = "redstone")]
+    namespace: String,
+
+    /// Pack format of the datapack, which depends on the game version, such as 15 for 1.20.1.
+    #[arg(long, default_value_t = 15)]
+    pack_format: u32,
+
+    /// Also write a `<output>_clear` function that clears every bit of the ROM, as placed by the
+    /// `mcfunction` format.
+    #[arg(long)]
+    clear: bool,
+
+    /// Also write a `<output>_verify` function that checks the blocks of every byte of the ROM
+    /// placed by the `mcfunction` format, telling which bytes differ and how many do.
+    #[arg(long)]
+    verify: bool,
+
+    /// Also write `<output>_patch` and `<output>_unpatch` functions that only place the bits that
+    /// differ from this earlier ROM, as written by the `bin` format, and that change them back.
+    #[arg(long, value_name = "ROM")]
+    patch_from: Option<PathBuf>,
+
+    /// Only check that the input assembles, without writing anything.
+    #[arg(long, conflicts_with = "disassemble")]
+    check: bool,
+
+    /// Don't print warnings, such as for unreachable code.
+    #[arg(long)]
+    no_warn: bool,
+
+    /// Fail when there are any warnings, without writing the output.
+    #[arg(long, conflicts_with = "no_warn")]
+    warnings_as_errors: bool,
+
+    /// Warn when the program uses more than this percentage of the ROM.
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+    usage_warning: Option<u8>,
+
+    /// Don't print how many bytes of the ROM the program uses.
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Overwrite output files that already exist.
+    #[arg(long)]
+    force: bool,
+
+    /// Assemble again whenever the input or a file it includes changes, until interrupted.
+    #[arg(long)]
+    watch: bool,
+
+    /// Format of errors and warnings.
+    #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+    message_format: MessageFormat,
+
+    /// When to color errors and warnings.
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Report only the first error in the program instead of every invalid line.
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Reject pseudo-instructions such as `ldi` and branches to labels, allowing only the
+    /// instructions of the CPU.
+    #[arg(long)]
+    strict: bool,
+
+    /// What unused ROM bytes are filled with: `halt`, a byte such as `0xE0`, or an instruction of
+    /// one byte such as `nop`. The default fills every gap with `halt`s that jump to themselves,
+    /// after one or two `nop`s (`mov r0 r0`) to line them up, so a program that runs into unused
+    /// ROM stops there, except in a gap too short for a jump, which only holds `nop`s. A pad of
+    /// `0` decodes to `sub r0`, which clears A.
+    #[arg(long, default_value = "halt", value_parser = parse_pad)]
+    pad: Pad,
+
+    /// Define a constant before the first line, as `.equ NAME VALUE` would, with a VALUE of 1
+    /// when omitted. The program can test it with `.ifdef` but can't redefine it. Repeatable.
+    #[arg(short = 'D', long = "define", value_name = "NAME[=VALUE]", value_parser = parse_define)]
+    defines: Vec<(String, i64)>,
+
+    /// Read defaults for the other options from this file instead of `redstone-assembler.toml`
+    /// in the working directory, if there is one. Options given here take precedence over it.
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    #[command(flatten)]
+    layout: LayoutArgs,
+}
+
+/// How the `mcfunction`, `schem`, `litematic` and `structure` formats place the ROM's bits,
+/// relative to where it is placed from.
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Layout")]
+struct LayoutArgs {
+    /// Bytes in every row, which runs along x.
+    #[arg(long, default_value_t = Layout::default().size_x)]
+    size_x: usize,
+
+    /// Distance along x between the bytes of a row.
+    #[arg(long, default_value_t = Layout::default().stride_x)]
+    stride_x: isize,
+
+    /// Position along x of the first byte of every row.
+    #[arg(long, default_value_t = Layout::default().offset_x)]
+    offset_x: isize,
+
+    /// Rows of bytes, which are stacked along y.
+    #[arg(long, default_value_t = Layout::default().size_y)]
+    size_y: usize,
+
+    /// Distance along y between rows.
+    #[arg(long, default_value_t = Layout::default().stride_y)]
+    stride_y: isize,
+
+    /// Position along y of the first row.
+    #[arg(long, default_value_t = Layout::default().offset_y)]
+    offset_y: isize,
+
+    /// Distance along z between the bits of a byte.
+    #[arg(long, default_value_t = Layout::default().stride_z)]
+    stride_z: isize,
+
+    /// Position along z of the first bit of every byte.
+    #[arg(long, default_value_t = Layout::default().offset_z)]
+    offset_z: isize,
+
+    /// Block with an optional state to place for every set bit.
+    #[arg(long, default_value_t = Layout::default().one)]
+    one_block: String,
+
+    /// Block with an optional state to place for every clear bit.
+    #[arg(long, default_value_t = Layout::default().zero)]
+    zero_block: String,
+
+    /// Order in which the bits of every byte are placed along z.
+    #[arg(long, value_enum, default_value_t = BitOrder::Msb)]
+    bit_order: BitOrder,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum MessageFormat {
+    /// Sentences with the source line underlined.
+    Human,
+    /// One JSON object per line with the `severity`, `file`, `line`, `column`, `message` and
+    /// `help`, where `file` is `null` for standard input and any but `severity` and `message`
+    /// are `null` when unknown.
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// Color when standard error is a terminal and `NO_COLOR` is not set.
+    Auto,
+    Always,
+    Never,
+}
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Prints errors and warnings to standard error.
+#[derive(Clone, Copy)]
+struct Reporter {
+    format: MessageFormat,
+    color: bool,
+}
+
+impl Reporter {
+    /// Prints an error or warning in a program, where `file` is the assembled file.
+    fn report(self, severity: &str, file: Option<&str>, e: &AssembleError) {
+        if self.format == MessageFormat::Json {
+            let file = match &e.file {
+                Some(path) => Some(path.display().to_string()),
+                None => file.map(str::to_string),
+            };
+            eprintln!(
+                "{{\"severity\":\"{severity}\",\"file\":{},\"line\":{},\"column\":{},\"message\":{},\"help\":{}}}",
+                file.as_deref().map_or("null".to_string(), json_string),
+                e.line,
+                e.column.map_or("null".to_string(), |column| column.to_string()),
+                json_string(&e.message),
+                e.help.as_deref().map_or("null".to_string(), json_string),
+            );
+            return;
+        }
+        let mut text = e.to_string();
+        // With a column, the last line holds the carets under the token, followed by any help.
+        if self.color && e.column.is_some() {
+            let caret_line = text.rfind('\n').map_or(0, |i| i + 1);
+            let start = caret_line + text[caret_line..].find('^').unwrap_or(0);
+            let end = text.rfind('^').map_or(start, |i| i + 1);
+            let color = if severity == "warning" { YELLOW } else { RED };
+            let help = &text[end..];
+            let help = if help.is_empty() {
+                String::new()
+            } else {
+                format!("{CYAN}{help}{RESET}")
+            };
+            text = format!(
+                "{}{color}{}{RESET}{help}",
+                &text[..start],
+                &text[start..end]
+            );
+        }
+        self.print(severity, &text);
+    }
+
+    /// Prints an error or warning that is not about a line of the program.
+    fn report_message(self, severity: &str, message: &str) {
+        match self.format {
+            MessageFormat::Human => self.print(severity, message),
+            MessageFormat::Json => eprintln!(
+                "{{\"severity\":\"{severity}\",\"file\":null,\"line\":null,\"column\":null,\"message\":{},\"help\":null}}",
+                json_string(message)
+            ),
+        }
+    }
+
+    /// Prints a human readable message, after the `Warning:` or `Error:` keyword.
+    fn print(self, severity: &str, text: &str) {
+        match (severity, self.color) {
+            ("warning", true) => eprintln!("{YELLOW}Warning:{RESET} {text}"),
+            ("warning", false) => eprintln!("Warning: {text}"),
+            (_, true) => eprintln!("{RED}Error:{RESET} {text}"),
+            (_, false) => eprintln!("Error: {text}"),
+        }
+    }
 }
 
-fn main() {
-    let args = Args::parse();
+/// Quotes `value` as a JSON string.
+fn json_string(value: &str) -> String {
+    let mut json = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
 
-    let re = Regex::new(r".*[/\\]|\..*").unwrap();
-    let function_name = args
-        .output
-        .unwrap_or(re.replace_all(&args.input, "").to_string());
-    let input = fs::read_to_string(args.input).unwrap();
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum BitOrder {
+    /// Most significant bit first.
+    Msb,
+    /// Least significant bit first.
+    Lsb,
+}
 
-    let mut opcodes = vec![0; ROM_BYTES];
+impl Args {
+    fn reporter(&self) -> Reporter {
+        let color = match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                io::stderr().is_terminal()
+                    && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+            }
+        };
+        Reporter {
+            format: self.message_format,
+            color,
+        }
+    }
 
-    for (i, line) in input.lines().enumerate() {
-        match parse_instr(line.split_once("//").map(|x| x.0).unwrap_or(line)) {
-            Some(v) => opcodes[i] = v,
-            None => {
-                eprintln!("Line {i} does not contain a valid instruction `{line}`.");
-                return;
+    fn options(&self) -> Options {
+        Options {
+            fail_fast: self.fail_fast,
+            strict: self.strict,
+            pad: self.pad,
+            defines: self.defines.clone(),
+        }
+    }
+}
+
+impl LayoutArgs {
+    fn layout(&self) -> Layout {
+        Layout {
+            size_x: self.size_x,
+            stride_x: self.stride_x,
+            offset_x: self.offset_x,
+            size_y: self.size_y,
+            stride_y: self.stride_y,
+            offset_y: self.offset_y,
+            stride_z: self.stride_z,
+            offset_z: self.offset_z,
+            one: self.one_block.clone(),
+            zero: self.zero_block.clone(),
+            lsb_first: self.bit_order == BitOrder::Lsb,
+        }
+    }
+}
+
+/// File in the working directory that defaults for the options are read from.
+const CONFIG_FILE: &str = "redstone-assembler.toml";
+
+/// Parses the command line, taking the options it doesn't give from the config file, so that
+/// options given on the command line take precedence over the config file, which takes
+/// precedence over the built-in defaults.
+fn parse_args() -> Args {
+    let mut command = Args::command();
+    let matches = command.clone().get_matches();
+    let config = matches.get_one::<PathBuf>("config");
+    let path = config.map_or(Path::new(CONFIG_FILE), PathBuf::as_path);
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if config.is_none() && e.kind() == io::ErrorKind::NotFound => {
+            return Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+        }
+        Err(e) => command
+            .error(
+                ErrorKind::Io,
+                format!("Could not read `{}`: {e}.", path.display()),
+            )
+            .exit(),
+    };
+    let defaults = config_args(&command, &matches, &text).unwrap_or_else(|e| {
+        command
+            .error(ErrorKind::InvalidValue, format!("`{}` {e}", path.display()))
+            .exit()
+    });
+    let mut cli = env::args_os();
+    Args::parse_from(
+        cli.next()
+            .into_iter()
+            .chain(defaults.map(Into::into))
+            .chain(cli),
+    )
+}
+
+/// The options set by a config file of `option = value` lines, where `option` is the long name
+/// of an option, leaving out those given on the command line of `matches`. Values are TOML
+/// strings, numbers, booleans for flags, or arrays of them for repeatable options.
+fn config_args(
+    command: &Command,
+    matches: &ArgMatches,
+    text: &str,
+) -> Result<impl Iterator<Item = String>, String> {
+    let mut args = Vec::new();
+    let mut keys = HashSet::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let number = i + 1;
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!(
+                "line {number} is not an `option = value` line, such as `namespace = \"cpu\"`."
+            ));
+        };
+        let key = key.trim();
+        let long = key.replace('_', "-");
+        let arg = command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(&long) && arg.get_id() != "config")
+            .ok_or_else(|| format!("line {number} sets unknown option `{key}`."))?;
+        if !keys.insert(arg.get_id()) {
+            return Err(format!("line {number} sets option `{key}` again."));
+        }
+        let values = parse_config_value(value.trim()).ok_or_else(|| {
+            format!("line {number} does not give a string, number, boolean or array for `{key}`.")
+        })?;
+        if matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine) {
+            continue;
+        }
+        if arg.get_action().takes_values() {
+            args.extend(values.into_iter().map(|value| format!("--{long}={value}")));
+        } else {
+            match values.as_slice() {
+                [flag] if flag == "true" => args.push(format!("--{long}")),
+                [flag] if flag == "false" => {}
+                _ => {
+                    return Err(format!(
+                        "line {number} does not give `true` or `false` for flag `{key}`."
+                    ))
+                }
             }
         }
     }
+    Ok(args.into_iter())
+}
 
-    let path = format!("../active-world/world/datapacks/redstone_assembler/data/redstone/functions/{function_name}.mcfunction");
-    let mut file = File::create(path).unwrap();
+/// The values of a TOML value followed by an optional comment, several for an array.
+fn parse_config_value(text: &str) -> Option<Vec<String>> {
+    let (values, rest) = match text.strip_prefix('[') {
+        Some(mut rest) => {
+            let mut values = Vec::new();
+            loop {
+                rest = rest.trim_start();
+                if let Some(after) = rest.strip_prefix(']') {
+                    break (values, after);
+                }
+                let (value, after) = parse_config_scalar(rest)?;
+                values.push(value);
+                rest = after.trim_start();
+                if let Some(after) = rest.strip_prefix(',') {
+                    rest = after;
+                } else if !rest.starts_with(']') {
+                    return None;
+                }
+            }
+        }
+        None => {
+            let (value, rest) = parse_config_scalar(text)?;
+            (vec![value], rest)
+        }
+    };
+    let rest = rest.trim_start();
+    (rest.is_empty() || rest.starts_with('#')).then_some(values)
+}
 
-    let mut i = 0;
-    for y in (0..SIZE_Y).map(|y| y * STRIDE_Y + OFFSET_Y) {
-        for x in (0..SIZE_X).map(|x| x * STRIDE_X + OFFSET_X) {
-            write!(file, "{}", write_byte(x, y, opcodes[i])).unwrap();
-            i += 1;
+/// Splits a TOML string, number or boolean from the start of `text`.
+fn parse_config_scalar(text: &str) -> Option<(String, &str)> {
+    if let Some(rest) = text.strip_prefix('\'') {
+        let (value, rest) = rest.split_once('\'')?;
+        return Some((value.to_string(), rest));
+    }
+    if let Some(rest) = text.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Some((value, &rest[i + 1..])),
+                '\\' => value.push(match chars.next()?.1 {
+                    'n' => '\n',
+                    't' => '\t',
+                    c @ ('"' | '\\') => c,
+                    _ => return None,
+                }),
+                c => value.push(c),
+            }
+        }
+        return None;
+    }
+    let end = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | '.')))
+        .unwrap_or(text.len());
+    let (value, rest) = text.split_at(end);
+    let is_number = value
+        .trim_start_matches(['+', '-'])
+        .starts_with(|c: char| c.is_ascii_digit());
+    (is_number || value == "true" || value == "false").then(|| (value.replace('_', ""), rest))
+}
+
+fn main() -> ExitCode {
+    let args = parse_args();
+    let reporter = args.reporter();
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            reporter.report_message("error", &e);
+            ExitCode::FAILURE
         }
     }
 }
+
+/// Runs the command line, returning why it failed once any diagnostics have been printed.
+fn run(args: Args) -> Result<(), String> {
+    if let Some(byte) = args.explain {
+        let Some(instruction) = Instruction::decode(byte) else {
+            return Err(format!("Byte {byte:#04x} is not used by any instruction."));
+        };
+        let mut out = io::stdout().lock();
+        return write_explanation(&mut out, instruction)
+            .and_then(|()| out.flush())
+            .map_err(|e| format!("Could not write to standard output: {e}."));
+    }
+    if let Some(line) = &args.eval {
+        let Program { listing, .. } =
+            assemble_file(Path::new(""), line, args.options()).map_err(|errors| {
+                for e in &errors {
+                    args.reporter().report("error", None, e);
+                }
+                format!("Could not assemble `{line}`.")
+            })?;
+        let mut out = io::stdout().lock();
+        return listing
+            .iter()
+            .flat_map(|(_, bytes, _)| bytes)
+            .try_for_each(|&byte| match Instruction::decode(byte) {
+                Some(instruction) => write_explanation(&mut out, instruction),
+                None => writeln!(out, "{byte:#04x} = 0b{byte:08b}: not an instruction"),
+            })
+            .and_then(|()| out.flush())
+            .map_err(|e| format!("Could not write to standard output: {e}."));
+    }
+    if args.list_instructions {
+        let mut out = io::stdout().lock();
+        return write_instruction_set(&mut out)
+            .and_then(|()| out.flush())
+            .map_err(|e| format!("Could not write to standard output: {e}."));
+    }
+    if !is_identifier(&args.array_name) {
+        return Err(format!(
+            "Array name `{}` is not a valid C identifier.",
+            args.array_name
+        ));
+    }
+    let layout = args.layout.layout();
+    layout.validate()?;
+    if args.format.contains(&Format::Schem) {
+        layout.validate_schem()?;
+    }
+    if args.output.as_deref() == Some("-") && args.format.iter().unique().count() > 1 {
+        return Err("Only one format can be written to standard output.".to_string());
+    }
+    if args.output.as_deref() == Some("-") && args.clear {
+        return Err("The clear function can't be written to standard output.".to_string());
+    }
+    if args.output.as_deref() == Some("-") && args.verify {
+        return Err("The verify function can't be written to standard output.".to_string());
+    }
+    if args.output.as_deref() == Some("-") && args.patch_from.is_some() {
+        return Err("The patch functions can't be written to standard output.".to_string());
+    }
+    if args.output.as_deref() == Some("-") && args.stats {
+        return Err("Statistics can't be printed with the output to standard output.".to_string());
+    }
+    if args.output.as_deref() == Some("-") && args.init_datapack {
+        return Err("A datapack can't be written to standard output.".to_string());
+    }
+    if !is_function_name(&args.namespace) {
+        return Err(format!(
+            "Namespace `{}` is not a valid namespace, which may only contain `a-z`, `0-9`, `_`, `-` and `.`.",
+            args.namespace
+        ));
+    }
+    let input = match &args.input {
+        Some(input) => input.as_str(),
+        None if !io::stdin().is_terminal() => "-",
+        None => {
+            return Err(
+                "No input given, pass a file with `--input` or pipe the program in.".to_string(),
+            )
+        }
+    };
+    if !args.watch {
+        return assemble_input(&args, input, &layout, args.force);
+    }
+    if input == "-" {
+        return Err(
+            "Standard input can't be watched, pass a file with `--input` instead.".to_string(),
+        );
+    }
+    let (sender, events) = mpsc::channel();
+    let mut debouncer =
+        new_debouncer(DEBOUNCE, sender).map_err(|e| format!("Could not watch `{input}`: {e}."))?;
+    let mut directories = HashSet::new();
+    // Later assemblies overwrite the files written by the first.
+    let mut force = args.force;
+    loop {
+        // The files are watched before assembling so that saves during it are not missed.
+        let text = fs::read_to_string(input).unwrap_or_default();
+        let files = source_files(Path::new(input), &text, &args.defines);
+        let files = watch_files(&mut debouncer, &mut directories, &files)?;
+        while events.try_recv().is_ok() {}
+        match assemble_input(&args, input, &layout, force) {
+            Ok(()) => {
+                force = true;
+                if args.message_format == MessageFormat::Human {
+                    eprintln!(
+                        "Assembled `{input}`, watching {} file{} for changes.",
+                        files.len(),
+                        if files.len() == 1 { "" } else { "s" }
+                    );
+                }
+            }
+            Err(e) => args.reporter().report_message("error", &e),
+        }
+        wait_for_change(&events, &files)?;
+    }
+}
+
+/// How long watched files must go without changes before assembling again, so that an editor
+/// writing a file in several steps only causes one assembly.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Watches the directories of `files` in place of the `directories` watched so far, and returns
+/// the absolute paths of the files. Directories are watched rather than the files themselves,
+/// which editors often replace when saving and which may not exist yet.
+fn watch_files(
+    debouncer: &mut Debouncer<RecommendedWatcher>,
+    directories: &mut HashSet<PathBuf>,
+    files: &[PathBuf],
+) -> Result<Vec<PathBuf>, String> {
+    let files = files
+        .iter()
+        .filter_map(|file| {
+            let directory = file
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty());
+            let directory = fs::canonicalize(directory.unwrap_or(Path::new("."))).ok()?;
+            Some(directory.join(file.file_name()?))
+        })
+        .collect_vec();
+    let watched: HashSet<_> = files
+        .iter()
+        .filter_map(|file| file.parent().map(Path::to_path_buf))
+        .collect();
+    let watcher = debouncer.watcher();
+    for directory in directories.difference(&watched) {
+        // A directory that was removed is no longer watched anyway.
+        let _ = watcher.unwatch(directory);
+    }
+    for directory in watched.difference(directories) {
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Could not watch `{}`: {e}.", directory.display()))?;
+    }
+    *directories = watched;
+    Ok(files)
+}
+
+/// Waits until any of `files` changes and then stops changing for `DEBOUNCE`.
+fn wait_for_change(
+    events: &Receiver<DebounceEventResult>,
+    files: &[PathBuf],
+) -> Result<(), String> {
+    loop {
+        let changes = events
+            .recv()
+            .map_err(|_| "Stopped watching for changes.".to_string())?
+            .map_err(|e| format!("Could not watch for changes: {e}."))?;
+        if changes.iter().any(|change| files.contains(&change.path)) {
+            return Ok(());
+        }
+    }
+}
+
+/// Assembles or disassembles `input` and writes the outputs, returning why it failed once any
+/// diagnostics have been printed.
+fn assemble_input(args: &Args, input: &str, layout: &Layout, force: bool) -> Result<(), String> {
+    let name = match input {
+        "-" => "standard input".to_string(),
+        input => format!("`{input}`"),
+    };
+    let bytes = read_input(input).map_err(|e| format!("Could not read {name}: {e}."))?;
+    if args.disassemble {
+        let mut out = io::stdout().lock();
+        return write_disassembly(&mut out, &bytes)
+            .and_then(|()| out.flush())
+            .map_err(|e| format!("Could not write to standard output: {e}."));
+    }
+
+    let function_name = match (&args.output, input) {
+        (Some(output), _) => output.clone(),
+        (None, "-") => "out".to_string(),
+        (None, input) => function_name(Path::new(input)),
+    };
+    if (args.format.contains(&Format::Mcfunction)
+        || args.clear
+        || args.verify
+        || args.patch_from.is_some())
+        && !is_function_name(&function_name)
+    {
+        return Err(format!(
+            "Output name `{function_name}` is not a valid function name, which may only contain `a-z`, `0-9`, `_`, `-` and `.`."
+        ));
+    }
+    let text = String::from_utf8(bytes)
+        .map_err(|_| format!("Could not read {name}: it is not valid UTF-8."))?;
+    let file = Some(input).filter(|&input| input != "-");
+    let Program {
+        rom,
+        listing,
+        instructions,
+        labels,
+        constants,
+        warnings,
+    } = assemble_file(Path::new(input), &text, args.options()).map_err(|errors| {
+        for e in &errors {
+            args.reporter().report("error", file, e);
+        }
+        format!(
+            "Could not assemble {name} due to {} error{}.",
+            errors.len(),
+            if errors.len() == 1 { "" } else { "s" }
+        )
+    })?;
+    let used: usize = listing.iter().map(|(_, bytes, _)| bytes.len()).sum();
+    let usage_warning = match args.usage_warning {
+        Some(percent) if used * 100 > usize::from(percent) * ROM_BYTES => Some(format!(
+            "The program uses {used} of the {ROM_BYTES} bytes of the ROM, more than {percent}%."
+        )),
+        _ => None,
+    };
+    if !args.no_warn {
+        for warning in &warnings {
+            args.reporter().report("warning", file, warning);
+        }
+        if let Some(warning) = &usage_warning {
+            args.reporter().report_message("warning", warning);
+        }
+        if args.warnings_as_errors && (!warnings.is_empty() || usage_warning.is_some()) {
+            return Err(format!(
+                "Could not assemble {name} as warnings are treated as errors."
+            ));
+        }
+    }
+    if !args.quiet && args.message_format == MessageFormat::Human {
+        eprintln!("Used {used}/{ROM_BYTES} bytes of the ROM.");
+    }
+    if args.stats {
+        let mut out = io::stdout().lock();
+        write_stats(&mut out, &instructions)
+            .and_then(|()| out.flush())
+            .map_err(|e| format!("Could not write to standard output: {e}."))?;
+    }
+    if args.preview {
+        let mut out = io::stdout().lock();
+        return write_preview(&mut out, &rom, layout)
+            .and_then(|()| out.flush())
+            .map_err(|e| format!("Could not write to standard output: {e}."));
+    }
+    if args.check {
+        return Ok(());
+    }
+
+    let function_dir = if args.init_datapack {
+        args.out_dir
+            .join("data")
+            .join(&args.namespace)
+            .join("functions")
+    } else {
+        args.out_dir.clone()
+    };
+    let to_stdout = args.output.as_deref() == Some("-");
+    let format_paths = args
+        .format
+        .iter()
+        .unique()
+        .filter(|_| !to_stdout)
+        .map(|&format| {
+            let dir = match format {
+                Format::Mcfunction => &function_dir,
+                _ => &args.out_dir,
+            };
+            (
+                format,
+                dir.join(format!("{function_name}.{}", format.extension())),
+            )
+        })
+        .collect_vec();
+    let clear_path = args
+        .clear
+        .then(|| function_dir.join(format!("{function_name}_clear.mcfunction")));
+    let verify_path = args
+        .verify
+        .then(|| function_dir.join(format!("{function_name}_verify.mcfunction")));
+    let patch_paths = args.patch_from.as_ref().map(|_| {
+        ["patch", "unpatch"]
+            .map(|suffix| function_dir.join(format!("{function_name}_{suffix}.mcfunction")))
+    });
+    let old_rom = match &args.patch_from {
+        Some(path) => {
+            let bytes =
+                fs::read(path).map_err(|e| format!("Could not read `{}`: {e}.", path.display()))?;
+            Some(<[u8; ROM_BYTES]>::try_from(bytes).map_err(|bytes| {
+                format!(
+                    "`{}` has {} bytes, but a ROM as written by the `bin` format has {ROM_BYTES}.",
+                    path.display(),
+                    bytes.len()
+                )
+            })?)
+        }
+        None => None,
+    };
+    let existing = format_paths
+        .iter()
+        .map(|(_, path)| path)
+        .chain(&clear_path)
+        .chain(&verify_path)
+        .chain(patch_paths.iter().flatten())
+        .chain(&args.listing)
+        .chain(&args.map)
+        .find(|path| path.exists());
+    if let Some(path) = existing.filter(|_| !force) {
+        return Err(format!(
+            "`{}` already exists, pass `--force` to overwrite it.",
+            path.display()
+        ));
+    }
+
+    // The `pack.mcmeta` of an existing datapack is kept, as it describes all of its functions.
+    let pack_mcmeta = args.out_dir.join("pack.mcmeta");
+    if args.init_datapack && (force || !pack_mcmeta.exists()) {
+        write_file(&pack_mcmeta, |out| {
+            write_pack_mcmeta(out, args.pack_format, &args.namespace)
+        })
+        .map_err(|e| format!("Could not write `{}`: {e}.", pack_mcmeta.display()))?;
+    }
+    if to_stdout {
+        let mut out = io::stdout().lock();
+        args.format[0]
+            .write(&mut out, &rom, &args.array_name, layout)
+            .and_then(|()| out.flush())
+            .map_err(|e| format!("Could not write to standard output: {e}."))?;
+    }
+    for (format, path) in format_paths {
+        write_file(&path, |out| {
+            format.write(out, &rom, &args.array_name, layout)
+        })
+        .map_err(|e| format!("Could not write `{}`: {e}.", path.display()))?;
+    }
+    if let Some(path) = clear_path {
+        write_file(&path, |out| write_mcfunction(out, &[0; ROM_BYTES], layout))
+            .map_err(|e| format!("Could not write `{}`: {e}.", path.display()))?;
+    }
+    if let Some(path) = verify_path {
+        write_file(&path, |out| write_verify(out, &rom, layout))
+            .map_err(|e| format!("Could not write `{}`: {e}.", path.display()))?;
+    }
+    if let (Some([patch, unpatch]), Some(old_rom)) = (patch_paths, old_rom) {
+        for (path, old, new) in [(patch, &old_rom, &rom), (unpatch, &rom, &old_rom)] {
+            write_file(&path, |out| write_patch(out, old, new, layout))
+                .map_err(|e| format!("Could not write `{}`: {e}.", path.display()))?;
+        }
+    }
+    if let Some(path) = &args.listing {
+        write_file(path, |out| write_listing(out, &listing))
+            .map_err(|e| format!("Could not write `{}`: {e}.", path.display()))?;
+    }
+    if let Some(path) = &args.map {
+        write_file(path, |out| write_map(out, &labels, &constants))
+            .map_err(|e| format!("Could not write `{}`: {e}.", path.display()))?;
+    }
+    Ok(())
+}
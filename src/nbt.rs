@@ -0,0 +1,85 @@
+//! Minecraft's Named Binary Tag format, as used by schematic files.
+
+use std::io::{self, Write};
+
+/// A tag, without its name.
+pub enum Tag {
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    ByteArray(Vec<u8>),
+    String(String),
+    List(Vec<Tag>),
+    Compound(Vec<(String, Tag)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Short(_) => 2,
+            Tag::Int(_) => 3,
+            Tag::Long(_) => 4,
+            Tag::ByteArray(_) => 7,
+            Tag::String(_) => 8,
+            Tag::List(_) => 9,
+            Tag::Compound(_) => 10,
+            Tag::IntArray(_) => 11,
+            Tag::LongArray(_) => 12,
+        }
+    }
+
+    /// Writes the tag as the root of a file, named `name`.
+    pub fn write_root(&self, out: &mut impl Write, name: &str) -> io::Result<()> {
+        out.write_all(&[self.id()])?;
+        write_string(out, name)?;
+        self.write_payload(out)
+    }
+
+    fn write_payload(&self, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            Tag::Short(value) => out.write_all(&value.to_be_bytes()),
+            Tag::Int(value) => out.write_all(&value.to_be_bytes()),
+            Tag::Long(value) => out.write_all(&value.to_be_bytes()),
+            Tag::ByteArray(values) => {
+                out.write_all(&(values.len() as i32).to_be_bytes())?;
+                out.write_all(values)
+            }
+            Tag::String(value) => write_string(out, value),
+            Tag::List(tags) => {
+                // An empty list has no elements to take the type from, so it is a list of End.
+                out.write_all(&[tags.first().map_or(0, Tag::id)])?;
+                out.write_all(&(tags.len() as i32).to_be_bytes())?;
+                tags.iter().try_for_each(|tag| tag.write_payload(out))
+            }
+            Tag::Compound(entries) => {
+                for (name, tag) in entries {
+                    out.write_all(&[tag.id()])?;
+                    write_string(out, name)?;
+                    tag.write_payload(out)?;
+                }
+                out.write_all(&[0])
+            }
+            Tag::IntArray(values) => {
+                out.write_all(&(values.len() as i32).to_be_bytes())?;
+                values
+                    .iter()
+                    .try_for_each(|value| out.write_all(&value.to_be_bytes()))
+            }
+            Tag::LongArray(values) => {
+                out.write_all(&(values.len() as i32).to_be_bytes())?;
+                values
+                    .iter()
+                    .try_for_each(|value| out.write_all(&value.to_be_bytes()))
+            }
+        }
+    }
+}
+
+/// Writes a string prefixed by its length. Only ASCII strings are written, for which Java's
+/// modified UTF-8 is plain UTF-8.
+fn write_string(out: &mut impl Write, value: &str) -> io::Result<()> {
+    out.write_all(&(value.len() as u16).to_be_bytes())?;
+    out.write_all(value.as_bytes())
+}
@@ -0,0 +1,249 @@
+//! Writers for the assembled ROM and its listing and disassembly.
+
+use crate::{
+    comment_start, skip_tokens, strip_comment, strip_label, Instruction, Pad, INSTRUCTIONS,
+    PSEUDO_INSTRUCTIONS, ROM_BYTES,
+};
+use itertools::Itertools;
+use std::io::{self, Write};
+
+#[cfg(feature = "minecraft")]
+mod minecraft;
+
+#[cfg(feature = "minecraft")]
+pub use minecraft::{
+    write_litematic, write_mcfunction, write_patch, write_preview, write_schem, write_structure,
+    write_verify, Layout,
+};
+
+/// Writes the ROM as Intel HEX data records of 16 bytes each, followed by an end-of-file record.
+pub fn write_ihex(out: &mut impl Write, opcodes: &[u8; ROM_BYTES]) -> io::Result<()> {
+    for (i, chunk) in opcodes.chunks(16).enumerate() {
+        let address = (i * 16) as u16;
+        let mut record = vec![chunk.len() as u8];
+        record.extend(address.to_be_bytes());
+        record.push(0x00); // data record
+        record.extend(chunk);
+        write_ihex_record(out, &record)?;
+    }
+    write_ihex_record(out, &[0x00, 0x00, 0x00, 0x01])
+}
+
+/// Writes an Intel HEX record with its checksum, the two's complement of the sum of its bytes.
+fn write_ihex_record(out: &mut impl Write, record: &[u8]) -> io::Result<()> {
+    let sum = record.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+    write!(out, ":")?;
+    for byte in record {
+        write!(out, "{byte:02X}")?;
+    }
+    writeln!(out, "{:02X}", sum.wrapping_neg())
+}
+
+/// Writes the ROM as rows like `0010: 8B 9A ... |..|`, showing unprintable bytes as `.`.
+pub fn write_hexdump(out: &mut impl Write, opcodes: &[u8; ROM_BYTES]) -> io::Result<()> {
+    for (i, row) in opcodes.chunks(16).enumerate() {
+        let gutter: String = row
+            .iter()
+            .map(|&byte| match byte {
+                b' '..=b'~' => byte as char,
+                _ => '.',
+            })
+            .collect();
+        writeln!(
+            out,
+            "{:04X}: {:02X} |{gutter}|",
+            i * 16,
+            row.iter().format(" ")
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the ROM as a Logisim memory image: a header followed by rows of space-separated bytes.
+pub fn write_logisim(out: &mut impl Write, opcodes: &[u8; ROM_BYTES]) -> io::Result<()> {
+    writeln!(out, "v2.0 raw")?;
+    for row in opcodes.chunks(16) {
+        writeln!(out, "{:02x}", row.iter().format(" "))?;
+    }
+    Ok(())
+}
+
+/// Writes the ROM as a C array named `name`, with its length defined as `NAME_LEN`.
+pub fn write_c_array(
+    out: &mut impl Write,
+    opcodes: &[u8; ROM_BYTES],
+    name: &str,
+) -> io::Result<()> {
+    let len = format!("{}_LEN", name.to_uppercase());
+    writeln!(out, "#include <stdint.h>\n")?;
+    writeln!(out, "#define {len} {ROM_BYTES}\n")?;
+    writeln!(out, "static const uint8_t {name}[{len}] = {{")?;
+    for row in opcodes.chunks(12) {
+        writeln!(out, "    {:#04x},", row.iter().format(", "))?;
+    }
+    writeln!(out, "}};")
+}
+
+/// Writes a `NAME = VALUE` line for every label, with its address in hex, and then for every
+/// constant.
+pub fn write_map(
+    out: &mut impl Write,
+    labels: &[(String, usize)],
+    constants: &[(String, i64)],
+) -> io::Result<()> {
+    for (name, address) in labels {
+        writeln!(out, "{name} = {address:#04x}")?;
+    }
+    for (name, value) in constants {
+        writeln!(out, "{name} = {value}")?;
+    }
+    Ok(())
+}
+
+/// Writes rows like `0x05  1100_0100  loop: breq  // exit`, giving every byte's address and
+/// value next to the instruction and comment of the source line that emitted it.
+pub fn write_listing(out: &mut impl Write, listing: &[(usize, Vec<u8>, String)]) -> io::Result<()> {
+    for (address, bytes, line) in listing {
+        let comment = comment_start(line).map_or("", |start| &line[start..]);
+        let (label, instr) = strip_label(strip_comment(line));
+        let instr = match label {
+            Some(label) => format!("{label}: {}", normalize(instr)),
+            None => normalize(instr),
+        };
+        let source = format!("{instr:<24}{comment}");
+        for (offset, byte) in bytes.iter().enumerate() {
+            let source = if offset == 0 { source.trim_end() } else { "" };
+            let row = format!(
+                "{:#04x}  {:04b}_{:04b}  {source}",
+                address + offset,
+                byte >> 4,
+                byte & 0b1111
+            );
+            writeln!(out, "{}", row.trim_end())?;
+        }
+    }
+    Ok(())
+}
+
+/// Separates the mnemonic of an instruction from its operands by a single space.
+fn normalize(instr: &str) -> String {
+    let mnemonic = instr.split_whitespace().next().unwrap_or("");
+    let operands = skip_tokens(instr, 1);
+    if operands.is_empty() {
+        mnemonic.to_string()
+    } else {
+        format!("{mnemonic} {operands}")
+    }
+}
+
+/// Writes the assembly of every byte of `rom`, leaving out the default padding after the program
+/// as it is added back when assembling.
+pub fn write_disassembly(out: &mut impl Write, rom: &[u8]) -> io::Result<()> {
+    let end = match <&[u8; ROM_BYTES]>::try_from(rom) {
+        Ok(&rom) => (0..ROM_BYTES)
+            .find(|&end| {
+                let mut padded = rom;
+                Pad::default().fill(&mut padded, end..ROM_BYTES);
+                padded == rom
+            })
+            .unwrap_or(ROM_BYTES),
+        Err(_) => rom.len(),
+    };
+    for &byte in &rom[..end] {
+        match Instruction::decode(byte) {
+            Some(instruction) => writeln!(out, "{instruction}")?,
+            None => writeln!(out, ".byte {byte:#04x} // ???")?,
+        }
+    }
+    Ok(())
+}
+
+/// Writes the ROM as one hex byte per line, as read by Verilog's `$readmemh`.
+pub fn write_readmemh(out: &mut impl Write, opcodes: &[u8; ROM_BYTES]) -> io::Result<()> {
+    for byte in opcodes {
+        writeln!(out, "{byte:02x}")?;
+    }
+    Ok(())
+}
+
+/// The group an instruction is listed in, by the block of opcodes it is encoded in.
+fn category(instruction: Instruction) -> &'static str {
+    match instruction.encode() >> 5 {
+        0b000 => "Arithmetic",
+        0b001 => "Logic",
+        0b010 => "Load/store",
+        0b100 | 0b101 => "Immediate",
+        0b110 if instruction.is_branch() => "Branch",
+        0b110 => "Status",
+        _ => "Move",
+    }
+}
+
+/// Writes every mnemonic with its operands and opcode, followed by the pseudo-instructions.
+pub fn write_instruction_set(out: &mut impl Write) -> io::Result<()> {
+    let groups = INSTRUCTIONS
+        .iter()
+        .group_by(|operands| category(operands.sample()));
+    for (category, group) in &groups {
+        writeln!(out, "{category}:")?;
+        for operands in group {
+            let instruction = operands.sample();
+            let (mnemonic, description) = (instruction.mnemonic(), operands.description());
+            writeln!(
+                out,
+                "  {mnemonic:<6}{description:<12}0b{:08b}",
+                instruction.encode()
+            )?;
+        }
+    }
+    writeln!(out, "Pseudo-instructions:")?;
+    for (mnemonic, description, expansion) in PSEUDO_INSTRUCTIONS {
+        writeln!(out, "  {mnemonic:<6}{description:<12}{expansion}")?;
+    }
+    Ok(())
+}
+
+/// Writes an instruction with a breakdown of the fields of its opcode.
+pub fn write_explanation(out: &mut impl Write, instruction: Instruction) -> io::Result<()> {
+    let byte = instruction.encode();
+    writeln!(out, "{byte:#04x} = 0b{byte:08b}: {instruction}")?;
+    let mut high = u8::BITS;
+    for (name, width, value) in instruction.fields() {
+        let low = high - width;
+        let bits = format!(
+            "{:0width$b}",
+            byte >> low & u8::MAX >> (8 - width),
+            width = width as usize
+        );
+        let range = match width {
+            1 => format!("bit {low}"),
+            _ => format!("bits {}-{low}", high - 1),
+        };
+        writeln!(
+            out,
+            "{}",
+            format!("  {range:<10}{bits:<10}{name} {value}").trim_end()
+        )?;
+        high = low;
+    }
+    Ok(())
+}
+
+/// Writes how often every mnemonic and category of `instructions` is used, most used first.
+pub fn write_stats(out: &mut impl Write, instructions: &[Instruction]) -> io::Result<()> {
+    let write_counts = |out: &mut dyn Write, names: Vec<&str>| -> io::Result<()> {
+        let counts = names.into_iter().counts();
+        for (name, count) in counts
+            .into_iter()
+            .sorted_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)))
+        {
+            writeln!(out, "{count:>5}  {name}")?;
+        }
+        Ok(())
+    };
+    writeln!(out, "Mnemonics:")?;
+    write_counts(out, instructions.iter().map(|i| i.mnemonic()).collect())?;
+    writeln!(out, "Categories:")?;
+    write_counts(out, instructions.iter().map(|&i| category(i)).collect())?;
+    writeln!(out, "{:>5}  instructions in total", instructions.len())
+}
@@ -0,0 +1,564 @@
+//! Writers that place the ROM in Minecraft, as a function or a schematic.
+
+use crate::gzip::write_gzip;
+use crate::nbt::Tag;
+use crate::ROM_BYTES;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+/// Where the torches of the ROM are placed relative to where it is placed from, as rows of
+/// bytes along x stacked along y, with the bits of every byte along z. The writers expect a
+/// layout that `validate` accepts.
+#[derive(Clone, Debug)]
+pub struct Layout {
+    /// Bytes in every row.
+    pub size_x: usize,
+    pub stride_x: isize,
+    pub offset_x: isize,
+    /// Rows of bytes.
+    pub size_y: usize,
+    pub stride_y: isize,
+    pub offset_y: isize,
+    pub stride_z: isize,
+    pub offset_z: isize,
+    /// Block with state placed for a set bit, such as `minecraft:lever[powered=true]`.
+    pub one: String,
+    /// Block with state placed for a clear bit.
+    pub zero: String,
+    /// Whether the bits of every byte run from its least rather than most significant bit.
+    pub lsb_first: bool,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout {
+            size_x: 32,
+            stride_x: -2,
+            offset_x: -2,
+            size_y: 4,
+            stride_y: 4,
+            offset_y: -15,
+            stride_z: -2,
+            offset_z: 0,
+            one: "minecraft:redstone_wall_torch[facing=east]".to_string(),
+            zero: "minecraft:air".to_string(),
+            lsb_first: false,
+        }
+    }
+}
+
+/// Most blocks the box around the bits may hold, far more than any ROM needs, which keeps the
+/// schematics of a layout spread out by mistake from taking all memory.
+const MAX_BOX_BLOCKS: u128 = 1 << 24;
+
+impl Layout {
+    /// Checks that there is room for every byte of the ROM, that every bit is placed within the
+    /// coordinates of a world and a box of at most `MAX_BOX_BLOCKS`, that no two bits are placed
+    /// at the same position, and that the blocks for set and clear bits are distinct blocks.
+    pub fn validate(&self) -> Result<(), String> {
+        for block in [&self.one, &self.zero] {
+            if !is_block(block) {
+                return Err(format!(
+                    "Block `{block}` is not a block with an optional state, such as `minecraft:lever[powered=true]`."
+                ));
+            }
+        }
+        if self.one == self.zero {
+            return Err(format!(
+                "Block `{}` can't be placed for both set and clear bits.",
+                self.one
+            ));
+        }
+        // Room for more bytes than a `usize` counts is room enough.
+        let slots = self.size_x.checked_mul(self.size_y);
+        if let Some(slots) = slots.filter(|&slots| slots < ROM_BYTES) {
+            return Err(format!(
+                "The layout has room for {slots} bytes, but the ROM has {ROM_BYTES}."
+            ));
+        }
+        for address in 0..ROM_BYTES {
+            if (0..8).any(|bit| self.position(address, bit).is_none()) {
+                return Err(format!(
+                    "The layout places byte {address} beyond the 32-bit coordinates of a world."
+                ));
+            }
+        }
+        let bits = rom_bits(&[0; ROM_BYTES], self).collect_vec();
+        let (_, [width, height, length]) = bounds(&bits);
+        if width as u128 * height as u128 * length as u128 > MAX_BOX_BLOCKS {
+            return Err(format!(
+                "The layout spreads the ROM over a box of {width} by {height} by {length} blocks, more than {MAX_BOX_BLOCKS}."
+            ));
+        }
+        let mut positions = HashSet::new();
+        let overlap = bits
+            .into_iter()
+            .find(|&(position, _)| !positions.insert(position));
+        if let Some(((x, y, z), _)) = overlap {
+            return Err(format!("The layout places two bits at ~{x} ~{y} ~{z}."));
+        }
+        Ok(())
+    }
+
+    /// Checks that the box around the bits fits in a schematic, which stores its size in shorts.
+    pub fn validate_schem(&self) -> Result<(), String> {
+        let bits = rom_bits(&[0; ROM_BYTES], self).collect_vec();
+        schem_size(bounds(&bits).1).map(|_| ())
+    }
+
+    /// The position of bit `bit` of the byte at `address`, counting from the first placed, or
+    /// `None` when it lies beyond the 32-bit coordinates of a world.
+    fn position(&self, address: usize, bit: usize) -> Option<Position> {
+        let axis = |index: usize, stride: isize, offset: isize| {
+            let coordinate = index as i128 * stride as i128 + offset as i128;
+            i32::try_from(coordinate)
+                .ok()
+                .map(|coordinate| coordinate as isize)
+        };
+        Some((
+            axis(address % self.size_x, self.stride_x, self.offset_x)?,
+            axis(address / self.size_x, self.stride_y, self.offset_y)?,
+            axis(bit, self.stride_z, self.offset_z)?,
+        ))
+    }
+}
+
+/// A position relative to where the ROM is placed from.
+type Position = (isize, isize, isize);
+
+/// The position of every bit of the ROM, with whether the bit is set.
+fn rom_bits<'a>(
+    opcodes: &'a [u8; ROM_BYTES],
+    layout: &'a Layout,
+) -> impl Iterator<Item = (Position, bool)> + 'a {
+    opcodes
+        .iter()
+        .enumerate()
+        .flat_map(move |(address, &byte)| {
+            (0..8).map(move |bit| {
+                let position = layout
+                    .position(address, bit)
+                    .expect("the layout is validated");
+                let shift = if layout.lsb_first { bit } else { 7 - bit };
+                (position, byte >> shift & 1 != 0)
+            })
+        })
+}
+
+/// The lowest corner of the box around `bits` and its size along x, y and z.
+fn bounds(bits: &[(Position, bool)]) -> ([isize; 3], [usize; 3]) {
+    let axis = |axis: fn(&Position) -> isize| {
+        let (min, max) = bits
+            .iter()
+            .map(|(position, _)| axis(position))
+            .minmax()
+            .into_option()
+            .unwrap();
+        (min, (max - min + 1) as usize)
+    };
+    let (x, width) = axis(|p| p.0);
+    let (y, height) = axis(|p| p.1);
+    let (z, length) = axis(|p| p.2);
+    ([x, y, z], [width, height, length])
+}
+
+/// Writes the ROM as a function that places it in the world. Every run of equal bits of a byte
+/// is placed with a single `fill` when the bits are next to each other. When they are apart, a
+/// run of clear bits is still a single `fill`, which only replaces the blocks of set bits so
+/// that the blocks between them stay.
+pub fn write_mcfunction(
+    out: &mut impl Write,
+    opcodes: &[u8; ROM_BYTES],
+    layout: &Layout,
+) -> io::Result<()> {
+    let bits = rom_bits(opcodes, layout).map(|(position, set)| (position, Some(set)));
+    write_bits(out, &bits.collect_vec(), layout)
+}
+
+/// Writes a function that changes the ROM placed by the function of `old` into `new`, placing
+/// only the bits that differ. Swapping the ROMs gives the function that changes it back.
+pub fn write_patch(
+    out: &mut impl Write,
+    old: &[u8; ROM_BYTES],
+    new: &[u8; ROM_BYTES],
+    layout: &Layout,
+) -> io::Result<()> {
+    let bits = rom_bits(old, layout)
+        .zip(rom_bits(new, layout))
+        .map(|((position, old), (_, new))| (position, Some(new).filter(|&new| new != old)));
+    write_bits(out, &bits.collect_vec(), layout)
+}
+
+/// Writes the commands that place every bit that is `Some`, given eight to a byte, using a
+/// `fill` for every run of equal bits of a byte next to each other, and for every run of clear
+/// bits that only replaces set bits otherwise.
+fn write_bits(
+    out: &mut impl Write,
+    bits: &[(Position, Option<bool>)],
+    layout: &Layout,
+) -> io::Result<()> {
+    for byte in bits.chunks(8) {
+        for (set, run) in &byte.iter().group_by(|&&(_, set)| set) {
+            let Some(set) = set else {
+                continue;
+            };
+            let block = if set { &layout.one } else { &layout.zero };
+            let run = run.collect_vec();
+            let ((x, y, z), _) = run[0];
+            match run.last() {
+                Some(&&((_, _, end), _)) if run.len() > 1 && layout.stride_z.abs() == 1 => {
+                    writeln!(out, "fill ~{x} ~{y} ~{z} ~{x} ~{y} ~{end} {block} replace")?
+                }
+                Some(&&((_, _, end), _)) if run.len() > 1 && !set => {
+                    let one = &layout.one;
+                    writeln!(
+                        out,
+                        "fill ~{x} ~{y} ~{z} ~{x} ~{y} ~{end} {block} replace {one}"
+                    )?
+                }
+                _ => {
+                    for ((x, y, z), _) in run {
+                        writeln!(out, "setblock ~{x} ~{y} ~{z} {block} replace")?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Scoreboard objective the verify function keeps its results in.
+const VERIFY_OBJECTIVE: &str = "rom_verify";
+
+/// Writes a function that checks the blocks of every byte of the ROM placed by the function,
+/// telling the one running it which bytes differ and then how many do.
+pub fn write_verify(
+    out: &mut impl Write,
+    opcodes: &[u8; ROM_BYTES],
+    layout: &Layout,
+) -> io::Result<()> {
+    let objective = VERIFY_OBJECTIVE;
+    writeln!(out, "scoreboard objectives add {objective} dummy")?;
+    writeln!(out, "scoreboard players set #mismatches {objective} 0")?;
+    let bits = rom_bits(opcodes, layout).collect_vec();
+    for (address, (byte, &opcode)) in bits.chunks(8).zip(opcodes).enumerate() {
+        let checks = byte
+            .iter()
+            .map(|&((x, y, z), set)| {
+                let block = if set { &layout.one } else { &layout.zero };
+                format!("if block ~{x} ~{y} ~{z} {block}")
+            })
+            .join(" ");
+        // The byte matches when every check succeeds, which `store success` records as 1.
+        writeln!(
+            out,
+            "execute store success score #byte {objective} {checks}"
+        )?;
+        writeln!(
+            out,
+            "execute if score #byte {objective} matches 0 run scoreboard players add #mismatches {objective} 1"
+        )?;
+        writeln!(
+            out,
+            "execute if score #byte {objective} matches 0 run tellraw @s \"Byte {address:#04x} does not hold {opcode:#04x}.\""
+        )?;
+    }
+    writeln!(
+        out,
+        "tellraw @s [{{\"score\":{{\"name\":\"#mismatches\",\"objective\":\"{objective}\"}}}},\" of the {ROM_BYTES} bytes differ.\"]"
+    )
+}
+
+/// Writes the torches of the ROM as seen from above, one layer of `#` for set and `.` for clear
+/// bits for every height from the top, with x running to the right and z downwards. Positions
+/// are numbered among those holding bits, leaving out the space between them.
+pub fn write_preview(
+    out: &mut impl Write,
+    opcodes: &[u8; ROM_BYTES],
+    layout: &Layout,
+) -> io::Result<()> {
+    let bits: HashMap<_, _> = rom_bits(opcodes, layout).collect();
+    let axis = |axis: fn(&Position) -> isize| bits.keys().map(axis).sorted().dedup().collect_vec();
+    let (xs, ys, zs) = (axis(|p| p.0), axis(|p| p.1), axis(|p| p.2));
+    for (i, &y) in ys.iter().rev().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "y ~{y}:")?;
+        for &z in &zs {
+            let row: String = xs
+                .iter()
+                .map(|&x| match bits.get(&(x, y, z)) {
+                    Some(true) => '#',
+                    Some(false) => '.',
+                    None => ' ',
+                })
+                .collect();
+            writeln!(out, "{}", row.trim_end())?;
+        }
+    }
+    Ok(())
+}
+
+/// Version of the Minecraft data in schematics, that of 1.20.1.
+const DATA_VERSION: i32 = 3465;
+
+/// Whether `block` looks like `namespace:name[property=value,...]`, where the namespace and
+/// state are optional.
+fn is_block(block: &str) -> bool {
+    let is_name = |name: &str| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_' | '-' | '.' | '/'))
+    };
+    let (id, state) = match block.split_once('[') {
+        Some((id, state)) => (id, state.strip_suffix(']')),
+        None => (block, Some("")),
+    };
+    let (namespace, name) = id.split_once(':').unwrap_or(("minecraft", id));
+    let Some(state) = state else {
+        return false;
+    };
+    is_name(namespace)
+        && is_name(name)
+        && (state.is_empty()
+            || state.split(',').all(|property| {
+                property
+                    .split_once('=')
+                    .is_some_and(|(key, value)| is_name(key) && is_name(value))
+            }))
+}
+
+/// The bits at each position of the box around the ROM, or `None` between them, indexed by x,
+/// then z, then y relative to its lowest corner, along with that corner and the box size.
+fn rom_box(
+    opcodes: &[u8; ROM_BYTES],
+    layout: &Layout,
+) -> (Vec<Option<bool>>, [isize; 3], [usize; 3]) {
+    let bits = rom_bits(opcodes, layout).collect_vec();
+    let ([x, y, z], [width, height, length]) = bounds(&bits);
+    let mut blocks = vec![None; width * height * length];
+    for (position, set) in bits {
+        let index = (position.0 - x) as usize
+            + (position.2 - z) as usize * width
+            + (position.1 - y) as usize * width * length;
+        blocks[index] = Some(set);
+    }
+    (blocks, [x, y, z], [width, height, length])
+}
+
+/// The width, height and length of a schematic of a box of `size`, which must fit in shorts.
+fn schem_size(size: [usize; 3]) -> Result<[i16; 3], String> {
+    match size.map(i16::try_from) {
+        [Ok(width), Ok(height), Ok(length)] => Ok([width, height, length]),
+        _ => {
+            let [width, height, length] = size;
+            Err(format!(
+                "The layout spreads the ROM over a box of {width} by {height} by {length} blocks, but a schematic is at most {} blocks along each axis.",
+                i16::MAX
+            ))
+        }
+    }
+}
+
+/// Writes the ROM as a gzipped Sponge schematic for WorldEdit, which places it like the function
+/// does when pasted with `//paste -m !structure_void` to keep the blocks between the bits.
+pub fn write_schem(
+    out: &mut impl Write,
+    opcodes: &[u8; ROM_BYTES],
+    layout: &Layout,
+) -> io::Result<()> {
+    let (bits, corner, size) = rom_box(opcodes, layout);
+    let [width, height, length] =
+        schem_size(size).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let blocks = bits
+        .iter()
+        .map(|bit| bit.map_or(0, |set| 1 + u8::from(set)))
+        .collect();
+    // The structure voids fill the space around the bits, which can be left out when pasting.
+    let palette = ["minecraft:structure_void", &layout.zero, &layout.one];
+    let palette_len = palette.len() as i32;
+    let palette = palette
+        .iter()
+        .enumerate()
+        .map(|(i, &block)| (block.to_string(), Tag::Int(i as i32)))
+        .collect();
+    let [x, y, z] = corner.map(|axis| Tag::Int(axis as i32));
+    let schematic = Tag::Compound(vec![
+        ("Version".into(), Tag::Int(2)),
+        ("DataVersion".into(), Tag::Int(DATA_VERSION)),
+        ("Width".into(), Tag::Short(width)),
+        ("Height".into(), Tag::Short(height)),
+        ("Length".into(), Tag::Short(length)),
+        // WorldEdit places the schematic relative to the paster by this offset of its corner.
+        (
+            "Metadata".into(),
+            Tag::Compound(vec![
+                ("WEOffsetX".into(), x),
+                ("WEOffsetY".into(), y),
+                ("WEOffsetZ".into(), z),
+            ]),
+        ),
+        ("Offset".into(), Tag::IntArray(vec![0; 3])),
+        ("PaletteMax".into(), Tag::Int(palette_len)),
+        ("Palette".into(), Tag::Compound(palette)),
+        // Palette numbers are varints, which take a single byte for a palette this small.
+        ("BlockData".into(), Tag::ByteArray(blocks)),
+    ]);
+    let mut nbt = Vec::new();
+    schematic.write_root(&mut nbt, "Schematic")?;
+    write_gzip(out, &nbt)
+}
+
+/// Writes the ROM as a gzipped structure for a structure block, which places the box around the
+/// bits from the corner of the structure block rather than from where the function is run.
+/// The space around the bits is left out, keeping the blocks between them.
+pub fn write_structure(
+    out: &mut impl Write,
+    opcodes: &[u8; ROM_BYTES],
+    layout: &Layout,
+) -> io::Result<()> {
+    let (bits, _, [width, height, length]) = rom_box(opcodes, layout);
+    let blocks = bits
+        .iter()
+        .enumerate()
+        .filter_map(|(i, bit)| {
+            let position = [i % width, i / (width * length), i / width % length];
+            bit.map(|set| {
+                Tag::Compound(vec![
+                    ("state".into(), Tag::Int(i32::from(set))),
+                    (
+                        "pos".into(),
+                        Tag::List(position.map(|axis| Tag::Int(axis as i32)).into()),
+                    ),
+                ])
+            })
+        })
+        .collect();
+    let size = [width, height, length].map(|axis| Tag::Int(axis as i32));
+    let structure = Tag::Compound(vec![
+        ("DataVersion".into(), Tag::Int(DATA_VERSION)),
+        ("size".into(), Tag::List(size.into())),
+        (
+            "palette".into(),
+            Tag::List(vec![block_state(&layout.zero), block_state(&layout.one)]),
+        ),
+        ("blocks".into(), Tag::List(blocks)),
+        ("entities".into(), Tag::List(Vec::new())),
+    ]);
+    let mut nbt = Vec::new();
+    structure.write_root(&mut nbt, "")?;
+    write_gzip(out, &nbt)
+}
+
+/// Version of the Litematica schematic format.
+const LITEMATIC_VERSION: i32 = 6;
+
+/// Writes the ROM as a gzipped Litematica schematic with a single region. As Litematica does
+/// not place air, clear bits are left out like the space around them when their block is air,
+/// so it is then meant for placing or checking a ROM that has no set bits yet.
+pub fn write_litematic(
+    out: &mut impl Write,
+    opcodes: &[u8; ROM_BYTES],
+    layout: &Layout,
+) -> io::Result<()> {
+    let (bits, corner, size) = rom_box(opcodes, layout);
+    // Litematica expects air first, which also fills the space around the bits.
+    let mut palette = vec!["minecraft:air"];
+    if layout.zero != "minecraft:air" {
+        palette.push(&layout.zero);
+    }
+    palette.push(&layout.one);
+    let (zero, one) = (palette.len() as u64 - 2, palette.len() as u64 - 1);
+    let states = bits
+        .iter()
+        .map(|&bit| bit.map_or(0, |set| if set { one } else { zero }))
+        .collect_vec();
+    let blocks = states.iter().filter(|&&state| state != 0).count();
+    let vector = |[x, y, z]: [i32; 3]| {
+        Tag::Compound(vec![
+            ("x".into(), Tag::Int(x)),
+            ("y".into(), Tag::Int(y)),
+            ("z".into(), Tag::Int(z)),
+        ])
+    };
+    let size = size.map(|axis| axis as i32);
+    let region = Tag::Compound(vec![
+        // Litematica places the region relative to the placement by this offset of its corner.
+        ("Position".into(), vector(corner.map(|axis| axis as i32))),
+        ("Size".into(), vector(size)),
+        (
+            "BlockStatePalette".into(),
+            Tag::List(palette.into_iter().map(block_state).collect()),
+        ),
+        // Litematica stores at least two bits per block, which fits this palette of three.
+        ("BlockStates".into(), Tag::LongArray(pack_bits(&states, 2))),
+        ("Entities".into(), Tag::List(Vec::new())),
+        ("TileEntities".into(), Tag::List(Vec::new())),
+        ("PendingBlockTicks".into(), Tag::List(Vec::new())),
+        ("PendingFluidTicks".into(), Tag::List(Vec::new())),
+    ]);
+    let schematic = Tag::Compound(vec![
+        ("MinecraftDataVersion".into(), Tag::Int(DATA_VERSION)),
+        ("Version".into(), Tag::Int(LITEMATIC_VERSION)),
+        (
+            "Metadata".into(),
+            Tag::Compound(vec![
+                ("Name".into(), Tag::String("ROM".into())),
+                ("Author".into(), Tag::String(String::new())),
+                ("Description".into(), Tag::String(String::new())),
+                ("RegionCount".into(), Tag::Int(1)),
+                ("TotalBlocks".into(), Tag::Int(blocks as i32)),
+                ("TotalVolume".into(), Tag::Int(bits.len() as i32)),
+                // Left at zero so the same ROM always gives the same file.
+                ("TimeCreated".into(), Tag::Long(0)),
+                ("TimeModified".into(), Tag::Long(0)),
+                ("EnclosingSize".into(), vector(size)),
+            ]),
+        ),
+        (
+            "Regions".into(),
+            Tag::Compound(vec![("ROM".into(), region)]),
+        ),
+    ]);
+    let mut nbt = Vec::new();
+    schematic.write_root(&mut nbt, "")?;
+    write_gzip(out, &nbt)
+}
+
+/// The block state tag of a block such as `minecraft:lever[powered=true]`.
+fn block_state(block: &str) -> Tag {
+    let (id, state) = block.split_once('[').unwrap_or((block, ""));
+    let id = if id.contains(':') {
+        id.to_string()
+    } else {
+        format!("minecraft:{id}")
+    };
+    let mut tag = vec![("Name".to_string(), Tag::String(id))];
+    let properties = state
+        .trim_end_matches(']')
+        .split(',')
+        .filter_map(|property| property.split_once('='))
+        .map(|(key, value)| (key.to_string(), Tag::String(value.to_string())))
+        .collect_vec();
+    if !properties.is_empty() {
+        tag.push(("Properties".to_string(), Tag::Compound(properties)));
+    }
+    Tag::Compound(tag)
+}
+
+/// Packs values of `bits` bits each into longs from their least significant bit, letting values
+/// span two longs as Litematica does.
+fn pack_bits(values: &[u64], bits: usize) -> Vec<i64> {
+    let mut longs = vec![0u64; (values.len() * bits).div_ceil(64)];
+    for (i, &value) in values.iter().enumerate() {
+        let start = i * bits;
+        longs[start / 64] |= value << (start % 64);
+        if start % 64 + bits > 64 {
+            longs[start / 64 + 1] |= value >> (64 - start % 64);
+        }
+    }
+    longs.into_iter().map(|long| long as i64).collect()
+}
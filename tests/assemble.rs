@@ -0,0 +1,2393 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the CLI quietly on `source` with `args`, returning its output and the files written to
+/// `out`.
+fn run(name: &str, source: impl AsRef<[u8]>, args: &[&str]) -> (Output, BTreeMap<String, Vec<u8>>) {
+    run_files(name, &[("test.rasm", source.as_ref())], args)
+}
+
+/// Runs the CLI quietly on `test.rasm` among `files`, given by their path, like `run`.
+fn run_files(
+    name: &str,
+    files: &[(&str, &[u8])],
+    args: &[&str],
+) -> (Output, BTreeMap<String, Vec<u8>>) {
+    let dir =
+        std::env::temp_dir().join(format!("redstone_assembler_{name}_{}", std::process::id()));
+    for (path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+        .args(["-i", "test.rasm", "--out-dir", "out", "--quiet"])
+        .args(args)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let mut written = BTreeMap::new();
+    read_files(&dir.join("out"), "", &mut written);
+    fs::remove_dir_all(&dir).unwrap();
+    (output, written)
+}
+
+/// Reads the files below `dir` into `files`, by their path relative to it.
+fn read_files(dir: &Path, prefix: &str, files: &mut BTreeMap<String, Vec<u8>>) {
+    for file in fs::read_dir(dir).into_iter().flatten() {
+        let path = file.unwrap().path();
+        let name = format!("{prefix}{}", path.file_name().unwrap().to_string_lossy());
+        if path.is_dir() {
+            read_files(&path, &format!("{name}/"), files);
+        } else {
+            files.insert(name, fs::read(path).unwrap());
+        }
+    }
+}
+
+/// Assembles `source` with the CLI and `args`, returning the written files by name.
+fn outputs(name: &str, source: &str, args: &[&str]) -> BTreeMap<String, Vec<u8>> {
+    let (output, written) = run(name, source, args);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success() && stderr.is_empty(), "{stderr}");
+    written
+}
+
+/// Assembles `source` with the CLI and `args`, returning the one written file.
+fn output(name: &str, source: &str, args: &[&str]) -> Vec<u8> {
+    let mut written = outputs(name, source, args).into_values();
+    let file = written.next().unwrap();
+    assert!(written.next().is_none(), "wrote more than one file");
+    file
+}
+
+/// Assembles `source` with the CLI and decodes the ROM from the generated mcfunction, ignoring
+/// any warnings.
+fn assemble(name: &str, source: &str) -> Vec<u8> {
+    let mcfunction = String::from_utf8(output(name, source, &["--no-warn"])).unwrap();
+    let mut rom = vec![0; 128];
+    for line in mcfunction.lines() {
+        let words: Vec<_> = line.split(' ').collect();
+        let coordinate = |word: &str| word[1..].parse::<i32>().unwrap();
+        // The default layout places byte `x + 32 * y` from ~-2 ~-15 with its bits every -2 along
+        // z, so a `fill` covers the bits from its first to its second z.
+        let (x, y, z, end, block) = match words[..] {
+            ["setblock", x, y, z, block, ..] => (x, y, z, z, block),
+            ["fill", x, y, z, _, _, end, block, ..] => (x, y, z, end, block),
+            _ => panic!("unexpected command `{line}`"),
+        };
+        let address = (coordinate(x) + 2) / -2 + 32 * ((coordinate(y) + 15) / 4);
+        for bit in coordinate(z) / -2..=coordinate(end) / -2 {
+            let mask = 0x80 >> bit;
+            if block.contains("torch") {
+                rom[address as usize] |= mask;
+            } else {
+                rom[address as usize] &= !mask;
+            }
+        }
+    }
+    rom
+}
+
+/// Assembles `source` with the CLI and `args`, expecting it to fail, and returns the errors
+/// reported before the final summary.
+fn assemble_errors(name: &str, source: &str, args: &[&str]) -> String {
+    let (output, written) = run(name, source, args);
+    assert!(
+        !output.status.success() && written.is_empty(),
+        "assembled without errors"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let errors = stderr
+        .trim_end()
+        .rsplit_once('\n')
+        .map_or("", |(errors, _)| errors);
+    let summary = &stderr[errors.len()..];
+    assert!(
+        summary
+            .trim()
+            .starts_with("Error: Could not assemble `test.rasm` due to "),
+        "{stderr}"
+    );
+    stderr[..errors.len() + 1].to_string()
+}
+
+fn assemble_error(name: &str, source: &str) -> String {
+    assemble_errors(name, source, &[])
+}
+
+#[test]
+fn brvc_unsupported() {
+    assert_eq!(
+        assemble_error("brvc", "brvc"),
+        "Error: Line 1 uses `brvc`, which is unsupported because `jmp` takes the opcode of branching on overflow clear.\n    brvc\n    ^^^^\n"
+    );
+    assert_eq!(
+        assemble_error("brvc_label", "brvc end\nend:"),
+        "Error: Line 1 uses `brvc`, which is unsupported because `jmp` takes the opcode of branching on overflow clear.\n    brvc end\n    ^^^^\n"
+    );
+    // Branching around a jump on overflow set does the same.
+    assert_eq!(
+        assemble(
+            "brvc_macro",
+            ".macro brvc target\nbrvs 1f\njmp \\target\n1:\n.endm\nbrvc end\nneg\nend:"
+        )[..7],
+        [
+            0b101_0_0110,
+            0b101_1_0000,
+            0b110_00_000,
+            0b101_0_0111,
+            0b101_1_0000,
+            0b110_00_001,
+            0b001_00_111
+        ]
+    );
+}
+
+#[test]
+fn nop() {
+    assert_eq!(assemble("nop", "nop\nmov r0 r0")[..2], [0b111_00_00_0; 2]);
+}
+
+#[test]
+fn halt() {
+    assert_eq!(
+        assemble("halt", "neg\nhalt\nhlt")[..7],
+        [
+            0b001_00_111,
+            0b101_0_0001,
+            0b101_1_0000,
+            0b110_00_001,
+            0b101_0_0100,
+            0b101_1_0000,
+            0b110_00_001
+        ]
+    );
+}
+
+#[test]
+fn clr() {
+    assert_eq!(
+        assemble("clr", "clr r0\nclr r1\nCLR R2\nclr r3")[..7],
+        [
+            0b000_00_000,
+            0b000_00_000,
+            0b111_00_01_0,
+            0b000_00_000,
+            0b111_00_10_0,
+            0b000_00_000,
+            0b111_00_11_0
+        ]
+    );
+    assert_eq!(
+        assemble_error("clr_special", "clr sp"),
+        "Error: Line 1 uses `sp`, which is not one of r0-r3.\n    clr sp\n        ^^\n"
+    );
+}
+
+#[test]
+fn ldi() {
+    assert_eq!(
+        assemble("ldi", "ldi 0xAB\n.equ VALUE 0x12\nldi VALUE + 1")[..4],
+        [0b100_0_1011, 0b100_1_1010, 0b100_0_0011, 0b100_1_0001]
+    );
+}
+
+#[test]
+fn hex_literals() {
+    assert_eq!(
+        assemble("hex_literals", "stl 0x0A\nldi 0xff\n.byte 0x7F, -0x1")[..5],
+        [0b100_0_1010, 0b100_0_1111, 0b100_1_1111, 0x7F, 0xFF]
+    );
+
+    assert_eq!(
+        assemble_error("hex_invalid", "stl 0xG"),
+        "Error: Line 1 has invalid expression `0xG`.\n    stl 0xG\n        ^^^\n"
+    );
+    assert_eq!(
+        assemble_error("hex_range", "stl 0x10"),
+        "Error: Line 1 uses immediate `0x10`, which is not in -8..=15.\n    stl 0x10\n        ^^^^\n"
+    );
+}
+
+#[test]
+fn numeric_literals() {
+    // Every operand takes decimal, hexadecimal and binary numbers alike.
+    assert_eq!(
+        assemble(
+            "numeric_literals",
+            "stl 0b1010\nstl 10\nlsl 0x4\nsts 0b1111"
+        )[..4],
+        [0b100_0_1010, 0b100_0_1010, 0b001_11_000, 0b010_1_1111]
+    );
+
+    assert_eq!(
+        assemble_error("numeric_range", "stl 16"),
+        "Error: Line 1 uses immediate `16`, which is not in -8..=15.\n    stl 16\n        ^^\n"
+    );
+    assert_eq!(
+        assemble_error("shift_range", "lsl 0b0"),
+        "Error: Line 1 uses shift amount `0b0`, which is not in 1..=4.\n    lsl 0b0\n        ^^^\n"
+    );
+    assert_eq!(
+        assemble_error("numeric_invalid", "stl 1_0"),
+        "Error: Line 1 has invalid expression `1_0`.\n    stl 1_0\n        ^^^\n"
+    );
+}
+
+#[test]
+fn char_literals() {
+    assert_eq!(
+        assemble(
+            "char_literals",
+            "ldi 'A'\n.byte '\\n', '\\0', '\\\\', '\\''\nstl '\\t' - 1"
+        )[..7],
+        [
+            0b100_0_0001,
+            0b100_1_0100,
+            b'\n',
+            0,
+            b'\\',
+            b'\'',
+            0b100_0_1000
+        ]
+    );
+
+    assert_eq!(
+        assemble_error("char_range", "stl 'a'"),
+        "Error: Line 1 uses immediate `'a'`, which is not in -8..=15.\n    stl 'a'\n        ^^^\n"
+    );
+    assert_eq!(
+        assemble_error("char_escape", ".byte '\\q'"),
+        "Error: Line 1 has invalid expression `'\\q'`.\n    .byte '\\q'\n          ^^^^\n"
+    );
+    assert_eq!(
+        assemble_error("char_long", ".byte 'ab'"),
+        "Error: Line 1 has invalid expression `'ab'`.\n    .byte 'ab'\n          ^^^^\n"
+    );
+}
+
+#[test]
+fn negative_immediates() {
+    // Negative operands are encoded in two's complement within their field.
+    assert_eq!(
+        assemble(
+            "negative_immediates",
+            "stl -1\nstl -8\nsth -1\nsdl -3\n.byte -2"
+        )[..5],
+        [0b100_0_1111, 0b100_0_1000, 0b100_1_1111, 0b101_0_1101, 0xFE]
+    );
+
+    assert_eq!(
+        assemble_error("negative_range", "stl -9"),
+        "Error: Line 1 uses immediate `-9`, which is not in -8..=15.\n    stl -9\n        ^^\n"
+    );
+    assert_eq!(
+        assemble_error("negative_byte", ".byte -129"),
+        "Error: Line 1 uses `.byte` value `-129`, which is not in -128..=255.\n    .byte -129\n          ^^^^\n"
+    );
+}
+
+#[test]
+fn jmp_label() {
+    assert_eq!(
+        assemble("jmp_label", ".org 0x21\nloop:\njmp end\njmp loop\nend:")[0x21..0x27],
+        [
+            0b101_0_0111,
+            0b101_1_0010,
+            0b110_00_001,
+            0b101_0_0001,
+            0b101_1_0010,
+            0b110_00_001
+        ]
+    );
+    assert_eq!(assemble("jmp_bare", "jmp")[0], 0b110_00_001);
+    assert_eq!(
+        assemble_error("jmp_undefined", "jmp nowhere"),
+        "Error: Line 1 references undefined label `nowhere`.\n    jmp nowhere\n        ^^^^^^^\n"
+    );
+}
+
+#[test]
+fn labels() {
+    // A label may share its line with an instruction, and several may mark one address.
+    let source = "start: neg\nloop:\nagain: dec\nbrne loop\njmp again\njmp start";
+    assert_eq!(
+        assemble("labels", source)[..11],
+        [
+            0b001_00_111,
+            0b001_11_111,
+            0b101_0_0001,
+            0b101_1_0000,
+            0b110_00_101,
+            0b101_0_0001,
+            0b101_1_0000,
+            0b110_00_001,
+            0b101_0_0000,
+            0b101_1_0000,
+            0b110_00_001
+        ]
+    );
+
+    assert_eq!(
+        assemble_error("label_redefined", "a:\nneg\na:"),
+        "Error: Line 3 redefines label `a`.\n    a:\n    ^\n"
+    );
+    assert_eq!(
+        assemble_error("label_constant", ".equ X 1\nX:"),
+        "Error: Line 2 defines label `X`, which is already a constant.\n    X:\n    ^\n"
+    );
+}
+
+#[test]
+fn forward_references() {
+    // Labels can be used before the line defining them, in branches and operands alike.
+    assert_eq!(
+        assemble(
+            "forward_references",
+            "jmp end\n.byte end, end - 1\nldi end\nend:"
+        )[..7],
+        [
+            0b101_0_0111,
+            0b101_1_0000,
+            0b110_00_001,
+            7,
+            6,
+            0b100_0_0111,
+            0b100_1_0000
+        ]
+    );
+
+    // The first pass places every line, so where a line goes can't depend on a later label.
+    assert_eq!(
+        assemble_error("forward_org", ".org end\nend:"),
+        "Error: Line 1 uses undefined symbol `end`.\n    .org end\n         ^^^\n"
+    );
+    assert_eq!(
+        assemble_error("forward_fill", ".fill end, 1\nend:"),
+        "Error: Line 1 uses undefined symbol `end`.\n    .fill end, 1\n          ^^^\n"
+    );
+}
+
+#[test]
+fn label_arithmetic() {
+    // `end + SKIP` is past the two bytes at `end`, and `start - 1` is the byte before `start`.
+    let source = ".equ SKIP 2
+jmp end + SKIP
+neg
+end: neg
+not
+.org 0x40
+start: breq start - 1";
+    let rom = assemble("label_arithmetic", source);
+    assert_eq!(rom[..3], [0b101_0_0110, 0b101_1_0000, 0b110_00_001]);
+    assert_eq!(rom[0x40..0x43], [0b101_0_1111, 0b101_1_0011, 0b110_00_100]);
+    assert_eq!(
+        assemble("branch_number", "brne 0x12")[..2],
+        [0b101_0_0010, 0b101_1_0001]
+    );
+
+    assert_eq!(
+        assemble_error("label_arithmetic_range", "end: jmp end - 1"),
+        "Error: Line 1 uses branch target `end - 1` (-1), which is not in 0..=127.\n    end: jmp end - 1\n             ^^^^^^^\n"
+    );
+    assert_eq!(
+        assemble_error("label_arithmetic_undefined", "jmp nowhere + 1"),
+        "Error: Line 1 uses undefined symbol `nowhere`.\n    jmp nowhere + 1\n        ^^^^^^^^^^^\n"
+    );
+}
+
+#[test]
+fn constants() {
+    // `.set` constants can be redefined, and every line uses the value defined before it.
+    let source = ".equ A 3\n.set B A + 1\n.byte A, B\n.set B B * 2\n.byte B";
+    assert_eq!(assemble("constants", source)[..3], [3, 4, 8]);
+
+    assert_eq!(
+        assemble_error("equ_redefined", ".equ X 1\n.equ X 2"),
+        "Error: Line 2 redefines constant `X`, use `.set` to allow redefinition.\n    .equ X 2\n         ^\n"
+    );
+    assert_eq!(
+        assemble_error("set_to_equ", ".set X 1\n.equ X 2"),
+        "Error: Line 2 redefines constant `X`, use `.set` to allow redefinition.\n    .equ X 2\n         ^\n"
+    );
+    assert_eq!(
+        assemble_error("constant_invalid", ".equ 1X 2"),
+        "Error: Line 1 defines a constant with invalid name `1X`.\n    .equ 1X 2\n         ^^\n"
+    );
+    assert_eq!(
+        assemble_error("constant_label", "X:\n.equ X 1"),
+        "Error: Line 2 defines constant `X`, which is already a label.\n    .equ X 1\n         ^\n"
+    );
+}
+
+#[test]
+fn org() {
+    // `.org` may also stay at the current address.
+    let rom = assemble("org", "neg\n.org 4\nnot\n.org 5\ninc\n.org 0x7F\ndec");
+    assert_eq!(
+        [rom[0], rom[4], rom[5], rom[0x7F]],
+        [0b001_00_111, 0b001_01_111, 0b001_10_111, 0b001_11_111]
+    );
+
+    assert_eq!(
+        assemble_error("org_backwards", "neg\n.org 4\nneg\n.org 2"),
+        "Error: Line 4 moves the address back from 5 to 2.\n    .org 2\n         ^\n"
+    );
+    assert_eq!(
+        assemble_error("org_past_rom", ".org 128"),
+        "Error: Line 1 moves the address to 128, beyond the 128-byte ROM.\n    .org 128\n         ^^^\n"
+    );
+}
+
+#[test]
+fn byte_directive() {
+    // Negative values are stored in two's complement.
+    assert_eq!(
+        assemble("byte", ".byte 1, 0xFF, -1, -128, 'A'\n.byte 2")[..6],
+        [1, 0xFF, 0xFF, 0x80, b'A', 2]
+    );
+
+    assert_eq!(
+        assemble_error("byte_range", ".byte 1, 256"),
+        "Error: Line 1 uses `.byte` value `256`, which is not in -128..=255.\n    .byte 1, 256\n             ^^^\n"
+    );
+    assert_eq!(
+        assemble_error("byte_undefined", ".byte nowhere"),
+        "Error: Line 1 uses undefined symbol `nowhere`.\n    .byte nowhere\n          ^^^^^^^\n"
+    );
+}
+
+#[test]
+fn ascii() {
+    // Only `.asciz` ends the string with a zero byte.
+    assert_eq!(
+        assemble("ascii", ".ascii \"hi\\n\"\n.asciz \"a\\\"b\"\n.byte 1")[..8],
+        *b"hi\na\"b\0\x01"
+    );
+
+    assert_eq!(
+        assemble_error("ascii_unterminated", ".ascii \"abc"),
+        "Error: Line 1 has an invalid string literal.\n    .ascii \"abc\n           ^^^^\n"
+    );
+    assert_eq!(
+        assemble_error("asciz_escape", ".asciz \"a\\qb\""),
+        "Error: Line 1 has an invalid string literal.\n    .asciz \"a\\qb\"\n           ^^^^^^\n"
+    );
+}
+
+#[test]
+fn fill_space() {
+    assert_eq!(
+        assemble(
+            "fill_space",
+            ".space 2\n.fill 3, 0xAB\n.fill 1, -1\n.space 0\n.byte 1"
+        )[..7],
+        [0, 0, 0xAB, 0xAB, 0xAB, 0xFF, 1]
+    );
+
+    assert_eq!(
+        assemble_error("fill_arguments", ".fill 2"),
+        "Error: Line 1 has invalid `.fill` arguments.\n    .fill 2\n    ^^^^^^^\n"
+    );
+    assert_eq!(
+        assemble_error("space_arguments", ".space 2, 1"),
+        "Error: Line 1 has invalid `.space` arguments.\n    .space 2, 1\n    ^^^^^^^^^^^\n"
+    );
+    assert_eq!(
+        assemble_error("fill_value", ".fill 2, 256"),
+        "Error: Line 1 uses value `256`, which is not in -128..=255.\n    .fill 2, 256\n             ^^^\n"
+    );
+    assert_eq!(
+        assemble_error("fill_past_rom", "neg\n.fill 128, 1"),
+        "Error: Line 2 fills 128 bytes from address 1, past the 128-byte ROM.\n"
+    );
+}
+
+#[test]
+fn align() {
+    // The bytes skipped to reach the boundary are zero, and an aligned address stays put.
+    assert_eq!(
+        assemble(
+            "align",
+            "neg\n.align 4\nnot\n.align 2\n.align 8\n.align 8\ninc"
+        )[..9],
+        [0b001_00_111, 0, 0, 0, 0b001_01_111, 0, 0, 0, 0b001_10_111]
+    );
+    // Aligning may reach the end of the ROM.
+    assert_eq!(assemble("align_end", ".org 100\n.align 64")[100..], [0; 28]);
+
+    for alignment in ["3", "0", "-4"] {
+        assert_eq!(
+            assemble_error("align_power", &format!(".align {alignment}")),
+            format!("Error: Line 1 has `.align` argument `{alignment}`, which is not a power of two.\n    .align {alignment}\n           {}\n", "^".repeat(alignment.len()))
+        );
+    }
+    assert_eq!(
+        assemble_error("align_past_rom", "neg\n.align 256"),
+        "Error: Line 2 aligns the address to 256, beyond the 128-byte ROM.\n    .align 256\n           ^^^\n"
+    );
+}
+
+#[test]
+fn call_ret_unsupported() {
+    assert_eq!(
+        assemble_error("call", "call sub\nsub:"),
+        "Error: Line 1 uses `call`, which is unsupported because no instruction can jump to an address held in a register.\n    call sub\n    ^^^^\n"
+    );
+    assert_eq!(
+        assemble("ret_macro", ".macro ret\nhalt\n.endm\nret")[..3],
+        [0b101_0_0000, 0b101_1_0000, 0b110_00_001]
+    );
+}
+
+#[test]
+fn strict() {
+    let source =
+        "nop\nclr r1\nldi 5\nloop:\nbrne loop\nHLT\nmov r0 r0\nsdl 3\nsdh 0\nbrne\n.byte 0";
+    assert_eq!(
+        assemble_errors("strict", source, &["--strict"]),
+        "Error: Line 1 uses pseudo-instruction `nop`, which strict mode rejects, write mov r0 r0 instead.\n    nop\n    ^^^\n\
+         Error: Line 2 uses pseudo-instruction `clr`, which strict mode rejects, write sub r0, then mov r0 into r1-r3 instead.\n    clr r1\n    ^^^\n\
+         Error: Line 3 uses pseudo-instruction `ldi`, which strict mode rejects, write stl and sth instead.\n    ldi 5\n    ^^^\n\
+         Error: Line 5 branches to label `loop`, which strict mode rejects, load it into `adr` with sdl and sdh before `brne` instead.\n    brne loop\n         ^^^^\n\
+         Error: Line 6 uses pseudo-instruction `HLT`, which strict mode rejects, write sdl, sdh and jmp to itself instead.\n    HLT\n    ^^^\n"
+    );
+    assert_eq!(
+        output(
+            "strict_real",
+            "mov r0 r0\nsdl 3\nsdh 0\nbrne",
+            &["--strict", "-f", "bin"]
+        )[..4],
+        [0b111_00_00_0, 0b101_0_0011, 0b101_1_0000, 0b110_00_101]
+    );
+}
+
+#[test]
+fn push_pop_unsupported() {
+    assert_eq!(
+        assemble_error("push", "push r1"),
+        "Error: Line 1 uses `push`, which is unsupported because `sp` can't be written and `lds` and `sts` only take a fixed address.\n    push r1\n    ^^^^\n"
+    );
+    assert_eq!(
+        assemble(
+            "pop_macro",
+            ".macro pop reg\nlds 15\nmov r0 \\reg\n.endm\npop r2"
+        )[..2],
+        [0b010_0_1111, 0b111_00_10_0]
+    );
+}
+
+#[test]
+fn all_errors() {
+    let source = "add r4\nneg\njmp nowhere\n.org 0x100";
+    assert_eq!(
+        assemble_errors("all_errors", source, &[]),
+        "Error: Line 1 uses `r4`, which is not one of r0-r3.\n    add r4\n        ^^\n\
+         Error: Line 3 references undefined label `nowhere`.\n    jmp nowhere\n        ^^^^^^^\n\
+         Error: Line 4 moves the address to 256, beyond the 128-byte ROM.\n    .org 0x100\n         ^^^^^\n"
+    );
+    assert_eq!(
+        assemble_errors("fail_fast", source, &["--fail-fast"]),
+        "Error: Line 1 uses `r4`, which is not one of r0-r3.\n    add r4\n        ^^\n"
+    );
+}
+
+#[test]
+fn caret() {
+    assert_eq!(
+        assemble_error("caret_mov", "start:	mov r0 rX // copy"),
+        "Error: Line 1 uses `rX`, which is not one of r0-r3.\n    start:\tmov r0 rX // copy\n          \t       ^^\n"
+    );
+    assert_eq!(
+        assemble_error("caret_imm", "  stl 1 + 16"),
+        "Error: Line 1 uses immediate `1 + 16` (17), which is not in -8..=15.\n      stl 1 + 16\n          ^^^^^^\n"
+    );
+}
+
+#[test]
+fn suggest_mnemonic() {
+    assert_eq!(
+        assemble_error("suggest_add", "ad r1"),
+        "Error: Line 1 uses unknown instruction or macro `ad`.\n    ad r1\n    ^^ did you mean `add`?\n"
+    );
+    for (typo, mnemonic) in [
+        ("mvo", "mov"),
+        ("sbu", "sub"),
+        ("jpm", "jmp"),
+        ("hatl", "halt"),
+    ] {
+        assert!(
+            assemble_error("suggest", typo).ends_with(&format!("did you mean `{mnemonic}`?\n")),
+            "{typo}"
+        );
+    }
+    assert_eq!(
+        assemble_error("suggest_none", "frobnicate"),
+        "Error: Line 1 uses unknown instruction or macro `frobnicate`.\n    frobnicate\n    ^^^^^^^^^^\n"
+    );
+}
+
+#[test]
+fn rom_overflow() {
+    let fits = "neg\n".repeat(128);
+    assert_eq!(assemble("rom_full", &fits), [0b001_00_111; 128]);
+    assert_eq!(
+        assemble_error("rom_overflow", &format!("{fits}neg\nldi 0xFF")),
+        "Error: Line 129 does not fit in the 128-byte ROM, which the program exceeds by 3 bytes.\n"
+    );
+    assert_eq!(
+        assemble_error("rom_overflow_org", ".org 127\njmp end\nend:"),
+        "Error: Line 2 does not fit in the 128-byte ROM, which the program exceeds by 2 bytes.\n"
+    );
+    // A label after the last byte of a full ROM is past it, like a local label there.
+    assert_eq!(
+        assemble_error("rom_end_label", "jmp end\n.org 127\nneg\nend:"),
+        "Error: Line 1 uses branch target `end` (128), which is not in 0..=127.\n    jmp end\n        ^^^\n"
+    );
+    assert_eq!(
+        assemble_error("rom_end_local", "jmp 1f\n.org 127\nneg\n1:"),
+        "Error: Line 1 uses branch target `1f` (128), which is not in 0..=127.\n    jmp 1f\n        ^^\n"
+    );
+}
+
+#[test]
+fn bin_format() {
+    let source = "ldi 0xAB\nhalt";
+    let rom = output("bin_format", source, &["--format", "bin"]);
+    assert_eq!(rom.len(), 128);
+    assert_eq!(rom, assemble("bin_format_mcfunction", source));
+}
+
+#[test]
+fn mcfunction_format() {
+    // The program has mixed bytes, an all-zero byte and the `nop`s and `halt`s padding it up to
+    // `.org`.
+    let function = output("mcfunction", include_str!("golden/program.rasm"), &[]);
+    assert_eq!(
+        String::from_utf8(function).unwrap(),
+        include_str!("golden/mcfunction.mcfunction")
+    );
+}
+
+#[test]
+fn ihex_format() {
+    assert_eq!(
+        String::from_utf8(output(
+            "ihex",
+            include_str!("golden/program.rasm"),
+            &["-f", "ihex"]
+        ))
+        .unwrap(),
+        include_str!("golden/ihex.hex")
+    );
+}
+
+#[test]
+fn hexdump_format() {
+    let hexdump = output(
+        "hexdump",
+        include_str!("golden/program.rasm"),
+        &["-f", "hexdump"],
+    );
+    assert_eq!(
+        String::from_utf8(hexdump).unwrap(),
+        include_str!("golden/hexdump.txt")
+    );
+}
+
+#[test]
+fn logisim_format() {
+    let image = output(
+        "logisim",
+        include_str!("golden/program.rasm"),
+        &["-f", "logisim"],
+    );
+    assert_eq!(
+        String::from_utf8(image).unwrap(),
+        include_str!("golden/logisim.txt")
+    );
+}
+
+#[test]
+fn readmemh_format() {
+    let mem = output(
+        "readmemh",
+        include_str!("golden/program.rasm"),
+        &["-f", "readmemh"],
+    );
+    let mem = String::from_utf8(mem).unwrap();
+    let lines: Vec<_> = mem.lines().collect();
+    assert_eq!(lines.len(), 128);
+    assert_eq!(lines[..7], ["8b", "9a", "a2", "b0", "c1", "00", "e0"]);
+    assert_eq!(lines[0x70..0x72], ["48", "65"]);
+}
+
+#[test]
+fn c_array_format() {
+    let args = ["-f", "c-array", "--array-name", "program"];
+    let header = output("c_array", include_str!("golden/program.rasm"), &args);
+    assert_eq!(
+        String::from_utf8(header).unwrap(),
+        include_str!("golden/c_array.h")
+    );
+}
+
+#[test]
+fn schem_format() {
+    let schematic = output(
+        "schem",
+        include_str!("golden/program.rasm"),
+        &["-f", "schem"],
+    );
+    assert_eq!(schematic, include_bytes!("golden/schem.schem"));
+}
+
+#[test]
+fn litematic_format() {
+    let schematic = output(
+        "litematic",
+        include_str!("golden/program.rasm"),
+        &["-f", "litematic"],
+    );
+    assert_eq!(schematic, include_bytes!("golden/litematic.litematic"));
+}
+
+#[test]
+fn structure_format() {
+    let structure = output(
+        "structure",
+        include_str!("golden/program.rasm"),
+        &["-f", "structure"],
+    );
+    assert_eq!(structure, include_bytes!("golden/structure.nbt"));
+}
+
+#[test]
+fn listing() {
+    let source = ".equ N 3\nstart: ldi   N   // load\n  add r1\nloop:\n  breq // loop exit\n.ascii \"h  i\"\n";
+    let written = outputs(
+        "listing",
+        source,
+        &["--listing", "out/test.lst", "--no-warn"],
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&written["test.lst"]),
+        "0x00  1000_0011  start: ldi N            // load\n\
+         0x01  1001_0000\n\
+         0x02  0000_1100  add r1\n\
+         0x03  1100_0100  breq                    // loop exit\n\
+         0x04  0110_1000  .ascii \"h  i\"\n\
+         0x05  0010_0000\n\
+         0x06  0010_0000\n\
+         0x07  0110_1001\n"
+    );
+}
+
+#[test]
+fn symbol_map() {
+    let source = ".equ N 3\n.set M 1\nstart: ldi N\nloop:\nend: add r1\n.set M -2\nbreq loop\n";
+    let written = outputs(
+        "symbol_map",
+        source,
+        &["--map", "out/test.map", "--no-warn"],
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&written["test.map"]),
+        "start = 0x00\nend = 0x02\nloop = 0x02\nM = -2\nN = 3\n"
+    );
+}
+
+#[test]
+fn disassemble_round_trip() {
+    for start in [0, 128] {
+        let rom: Vec<u8> = (start..start + 128).map(|byte| byte as u8).collect();
+        let (disassembled, _) = run("disassemble", &rom, &["--disassemble"]);
+        assert!(disassembled.status.success());
+        let source = String::from_utf8(disassembled.stdout).unwrap();
+        assert_eq!(
+            output("reassemble", &source, &["-f", "bin", "--no-warn"]),
+            rom
+        );
+    }
+
+    let (disassembled, _) = run(
+        "disassemble_unused",
+        [0b011_00000, 0b111_10_11_1],
+        &["--disassemble"],
+    );
+    assert_eq!(
+        String::from_utf8(disassembled.stdout).unwrap(),
+        ".byte 0x60 // ???\nmov sp r3\n"
+    );
+}
+
+#[test]
+fn stdin_input() {
+    let dir = std::env::temp_dir().join(format!("redstone_assembler_stdin_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for args in [&["-i", "-", "-f", "bin"][..], &["-f", "bin"]] {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+            .args(args)
+            .current_dir(&dir)
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(b"ldi 0x5A").unwrap();
+        assert!(child.wait().unwrap().success());
+        assert_eq!(
+            fs::read(dir.join("out.bin")).unwrap()[..3],
+            [0x8A, 0x95, 0xA2]
+        );
+        fs::remove_file(dir.join("out.bin")).unwrap();
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn stdout_output() {
+    let source = "ldi 0x5A";
+    let (stdout, written) = run("stdout", source, &["-o", "-"]);
+    assert!(stdout.status.success() && written.is_empty());
+    assert_eq!(stdout.stdout, output("stdout_file", source, &[]));
+
+    let (stdout, _) = run("stdout_bin", source, &["-o", "-", "-f", "bin"]);
+    assert_eq!(stdout.stdout[..3], [0x8A, 0x95, 0xA2]);
+}
+
+#[test]
+fn multiple_formats() {
+    let args = ["-f", "mcfunction", "--format", "bin", "-f", "bin"];
+    let written = outputs("multiple_formats", "ldi 0x5A", &args);
+    assert_eq!(
+        written.keys().collect::<Vec<_>>(),
+        ["test.bin", "test.mcfunction"]
+    );
+    assert_eq!(
+        written["test.bin"],
+        assemble("multiple_formats_mcfunction", "ldi 0x5A")
+    );
+}
+
+#[test]
+fn blank_lines() {
+    assert_eq!(
+        assemble("blank_lines", "\nneg\n\n   \nnot\n\t\nend:\n\nhalt\n\n")[..5],
+        [
+            0b001_00_111,
+            0b001_01_111,
+            0b101_0_0010,
+            0b101_1_0000,
+            0b110_00_001
+        ]
+    );
+}
+
+#[test]
+fn comment_lines() {
+    let source = "// setup\nneg // negate\n    // indented note\n.macro twice\n// inside a macro\nnot\nnot\n.endm\ntwice\nend: // the end\n//\nhalt";
+    assert_eq!(
+        assemble("comment_lines", source)[..6],
+        [
+            0b001_00_111,
+            0b001_01_111,
+            0b001_01_111,
+            0b101_0_0011,
+            0b101_1_0000,
+            0b110_00_001
+        ]
+    );
+}
+
+#[test]
+fn addresses_follow_emitted_bytes() {
+    let source = "\n// header\n\nfirst: neg\n\n// gap\nsecond:\n\n  not\n.org 0x10\n\nthird: .byte first, second, third, fourth\n// trailing\nfourth:";
+    let rom = assemble("addresses", source);
+    assert_eq!(rom[..2], [0b001_00_111, 0b001_01_111]);
+    assert_eq!(rom[2..5], [0xE0, 0xE0, 0xA4]);
+    assert_eq!(rom[0x10..0x14], [0x00, 0x01, 0x10, 0x14]);
+}
+
+#[test]
+fn comments_in_literals() {
+    assert_eq!(
+        assemble("comment_url", ".ascii \"http://a\" // link")[..8],
+        *b"http://a"
+    );
+    assert_eq!(
+        assemble(
+            "comment_escaped",
+            ".ascii \"\\\"//\\\\\" // quote\n.byte '/', '\\'' // chars"
+        )[..6],
+        *b"\"//\\/'"
+    );
+}
+
+#[test]
+fn block_comments() {
+    let source = "neg /* negate */ /* twice */\n/* a // note\nnot\n*/ inc /* again\n*/\ndec // no /* block\n.ascii \"a/*b*/\" /* c */\n.byte '/', '*'";
+    assert_eq!(
+        assemble("block_comments", source)[..10],
+        [
+            0b001_00_111,
+            0b001_10_111,
+            0b001_11_111,
+            b'a',
+            b'/',
+            b'*',
+            b'b',
+            b'*',
+            b'/',
+            b'/'
+        ]
+    );
+    assert_eq!(
+        assemble_error("block_comment_unclosed", "neg\n/* never\nclosed"),
+        "Error: Line 2 opens a block comment that is never closed.\n"
+    );
+    // Errors point into the line as written, comments and all.
+    assert_eq!(
+        assemble_errors(
+            "block_comment_column",
+            "/* ab */ stl 99\nneg; /* é */ add r9 /* open\n*/ jmp 1_",
+            &[]
+        ),
+        "Error: Line 1 uses immediate `99`, which is not in -8..=15.\n    /* ab */ stl 99\n                 ^^\n\
+         Error: Line 2 uses `r9`, which is not one of r0-r3.\n    neg; /* é */ add r9 /* open\n                     ^^\n\
+         Error: Line 3 has invalid expression `1_`.\n    */ jmp 1_\n           ^^\n"
+    );
+}
+
+#[test]
+fn crlf_line_endings() {
+    let source = "start: neg\r\n\r\nmov r1 r2 // copy\r\n.ascii \"ab\"\r\r\nbreq\t \r";
+    assert_eq!(
+        assemble("crlf", source)[..5],
+        [0b001_00_111, 0b111_01_10_0, b'a', b'b', 0b110_00_100]
+    );
+}
+
+#[test]
+fn failure_messages() {
+    let (output, _) = run("summary", "add r4\nadd r5", &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.ends_with("\nError: Could not assemble `test.rasm` due to 2 errors.\n"));
+    assert_eq!(output.status.code(), Some(1));
+
+    let missing = Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+        .args(["-i", "missing/test.rasm"])
+        .output()
+        .unwrap();
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(String::from_utf8(missing.stderr)
+        .unwrap()
+        .starts_with("Error: Could not read `missing/test.rasm`: "));
+
+    let (output, _) = run(
+        "unwritable",
+        "neg",
+        &["-o", "../test.rasm/test", "-f", "bin"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("Error: Could not write `out/../test.rasm/test.bin`: "));
+}
+
+#[test]
+fn function_names() {
+    let dir = std::env::temp_dir().join(format!("redstone_assembler_names_{}", std::process::id()));
+    for (input, function) in [
+        ("./programs/test.asm", "test"),
+        ("my.cpu/prog.asm", "prog"),
+        ("programs/noext", "noext"),
+        ("two.dots.rasm", "two.dots"),
+        ("Weird Name!.rasm", "weird_name_"),
+    ] {
+        let path = dir.join(input);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "neg").unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+            .args(["-i", input, "--out-dir", "out"])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{input}");
+        assert!(
+            dir.join(format!("out/{function}.mcfunction")).exists(),
+            "{input}"
+        );
+    }
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        run("bad_name", "neg", &["-o", "Bad Name"]).0.stderr,
+        b"Error: Output name `Bad Name` is not a valid function name, which may only contain `a-z`, `0-9`, `_`, `-` and `.`.\n"
+    );
+}
+
+#[test]
+fn case_insensitive_mnemonics() {
+    assert_eq!(
+        assemble(
+            "mixed_case",
+            "ADD R1\nMov PC r2\nLDI 'a'\nBrEq end\nJmp\nend: NOP\nHALT\n.ascii \"ADD\"",
+        ),
+        assemble(
+            "lower_case",
+            "add r1\nmov pc r2\nldi 'a'\nbreq end\njmp\nend: nop\nhalt\n.ascii \"ADD\"",
+        )
+    );
+    assert_eq!(assemble("ascii_case", ".ascii \"Add\"")[..3], *b"Add");
+}
+
+#[test]
+fn register_aliases() {
+    let aliases = "add a\nadd x\nADD Y\nadd z\nmov sp x\nmov y a";
+    let canonical = "add r0\nadd r1\nadd r2\nadd r3\nmov sp r1\nmov r2 r0";
+    assert_eq!(
+        assemble("aliases", aliases)[..6],
+        assemble("canonical", canonical)[..6]
+    );
+    // A constant may still be named like an alias, as registers are never expressions.
+    assert_eq!(
+        assemble("alias_constant", ".equ x 3\nlds x\nsub x")[..2],
+        [0b010_0_0011, 0b000_01_000]
+    );
+}
+
+#[test]
+fn comma_separated_operands() {
+    let expected = assemble("spaces", "mov pc r1");
+    for source in ["mov pc,r1", "mov pc , r1", "mov pc, r1", "mov pc\t,\tr1"] {
+        assert_eq!(assemble("commas", source), expected, "{source}");
+    }
+}
+
+#[test]
+fn mov_special_destination() {
+    assert_eq!(assemble("mov_from_sp", "mov sp r2")[0], 0b111_10_10_1);
+    for reg in ["pc", "adr", "sp", "sr"] {
+        assert_eq!(
+            assemble_error("mov_to_special", &format!("mov r2 {reg}")),
+            format!(
+                "Error: Line 1 moves into `{reg}`, but `mov` can only write r0-r3.\n    mov r2 {reg}\n           {}\n",
+                "^".repeat(reg.len())
+            )
+        );
+    }
+}
+
+#[test]
+fn ssr_flags() {
+    for (flags, status) in [
+        ("1010", 0b1010),
+        ("0b0101", 0b0101),
+        ("N,Z", 0b1010),
+        ("nz", 0b1010),
+        ("z, n", 0b1010),
+        ("C", 0b0001),
+        ("nvzc", 0b1111),
+    ] {
+        assert_eq!(
+            assemble("ssr_flags", &format!("ssr {flags}"))[0],
+            0b110_1_0000 | status,
+            "{flags}"
+        );
+    }
+}
+
+#[test]
+fn custom_layout() {
+    let args = [
+        "--pad",
+        "0",
+        "--size-x",
+        "16",
+        "--stride-x",
+        "3",
+        "--offset-x",
+        "1",
+        "--size-y",
+        "8",
+        "--stride-y",
+        "-2",
+        "--offset-y",
+        "5",
+        "--stride-z",
+        "3",
+        "--offset-z",
+        "-8",
+    ];
+    let mcfunction = String::from_utf8(output("custom_layout", "neg", &args)).unwrap();
+    let lines: Vec<_> = mcfunction.lines().collect();
+    let torch = "minecraft:redstone_wall_torch[facing=east]";
+    // `neg` is 0b0010_0111, followed by bytes that are all clear.
+    assert_eq!(lines.len(), 6 + 127);
+    assert_eq!(
+        lines[0],
+        format!("fill ~1 ~5 ~-8 ~1 ~5 ~-5 minecraft:air replace {torch}")
+    );
+    assert_eq!(lines[1], format!("setblock ~1 ~5 ~-2 {torch} replace"));
+    assert_eq!(lines[5], format!("setblock ~1 ~5 ~13 {torch} replace"));
+    assert_eq!(
+        lines[6],
+        format!("fill ~4 ~5 ~-8 ~4 ~5 ~13 minecraft:air replace {torch}")
+    );
+    assert_eq!(
+        lines[6 + 15],
+        format!("fill ~1 ~3 ~-8 ~1 ~3 ~13 minecraft:air replace {torch}")
+    );
+
+    let (output, _) = run("small_layout", "neg", &["--size-x", "31"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: The layout has room for 124 bytes, but the ROM has 128.\n"
+    );
+    let (output, _) = run("overlapping_layout", "neg", &["--stride-z", "0"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: The layout places two bits at ~-2 ~-15 ~0.\n"
+    );
+    outputs(
+        "wide_layout",
+        "neg",
+        &["--size-x", "18446744073709551615", "--size-y", "2"],
+    );
+    let (output, _) = run(
+        "distant_layout",
+        "neg",
+        &["--stride-x", "9223372036854775807"],
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: The layout places byte 1 beyond the 32-bit coordinates of a world.\n"
+    );
+    let (output, files) = run(
+        "sparse_layout",
+        "neg",
+        &[
+            "--stride-x",
+            "100000",
+            "--stride-y",
+            "100000",
+            "-f",
+            "schem",
+        ],
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: The layout spreads the ROM over a box of 3100001 by 300001 by 15 blocks, more than 16777216.\n"
+    );
+    assert!(files.is_empty());
+    let (output, files) = run(
+        "long_layout",
+        "neg",
+        &[
+            "--stride-x",
+            "3000",
+            "--size-x",
+            "128",
+            "--size-y",
+            "1",
+            "-f",
+            "schem",
+        ],
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: The layout spreads the ROM over a box of 381001 by 1 by 15 blocks, but a schematic is at most 32767 blocks along each axis.\n"
+    );
+    assert!(files.is_empty());
+    outputs(
+        "long_layout_structure",
+        "neg",
+        &[
+            "--stride-x",
+            "3000",
+            "--size-x",
+            "128",
+            "--size-y",
+            "1",
+            "-f",
+            "structure",
+        ],
+    );
+}
+
+#[test]
+fn custom_blocks() {
+    let args = [
+        "--one-block",
+        "minecraft:lever[face=wall,facing=east,powered=true]",
+        "--zero-block",
+        "stone",
+    ];
+    let mcfunction = String::from_utf8(output("custom_blocks", "neg", &args)).unwrap();
+    assert!(mcfunction.starts_with(
+        "fill ~-2 ~-15 ~0 ~-2 ~-15 ~-2 stone replace minecraft:lever[face=wall,facing=east,powered=true]\n\
+         setblock ~-2 ~-15 ~-4 minecraft:lever[face=wall,facing=east,powered=true] replace\n"
+    ));
+    assert!(!mcfunction.contains("torch") && !mcfunction.contains("air"));
+
+    for block in [
+        "Stone",
+        "minecraft:lever[powered]",
+        "lever[powered=true",
+        "a:b:c",
+    ] {
+        let (output, _) = run("invalid_block", "neg", &["--one-block", block]);
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            format!("Error: Block `{block}` is not a block with an optional state, such as `minecraft:lever[powered=true]`.\n")
+        );
+    }
+}
+
+#[test]
+fn bit_order() {
+    let torches = |order| {
+        let mcfunction = output("bit_order", "neg", &["--bit-order", order]);
+        String::from_utf8(mcfunction)
+            .unwrap()
+            .lines()
+            .take_while(|line| line.split(' ').nth(1) == Some("~-2"))
+            .filter(|line| line.starts_with("setblock") && line.contains("torch"))
+            .map(|line| line.split(' ').nth(3).unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    // `neg` is 0b0010_0111.
+    assert_eq!(torches("msb"), ["~-4", "~-10", "~-12", "~-14"]);
+    assert_eq!(torches("lsb"), ["~0", "~-2", "~-4", "~-10"]);
+    assert_eq!(assemble("bit_order_opcode", "neg")[0], 0b0010_0111);
+}
+
+#[test]
+fn clear_function() {
+    let written = outputs("clear", "neg", &["--clear"]);
+    assert_eq!(
+        written.keys().collect::<Vec<_>>(),
+        ["test.mcfunction", "test_clear.mcfunction"]
+    );
+    let program = String::from_utf8_lossy(&written["test.mcfunction"]);
+    let clear = String::from_utf8_lossy(&written["test_clear.mcfunction"]);
+    assert!(program.contains("torch"));
+    // Every byte is cleared with one `fill`, from the position of its first bit to its last.
+    assert_eq!(clear.lines().count(), 128);
+    for (address, clear) in clear.lines().enumerate() {
+        let (x, y) = (
+            -2 - 2 * (address % 32) as i32,
+            -15 + 4 * (address / 32) as i32,
+        );
+        assert_eq!(
+            clear,
+            format!("fill ~{x} ~{y} ~0 ~{x} ~{y} ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]")
+        );
+    }
+
+    let (output, _) = run("clear_stdout", "neg", &["--clear", "-o", "-"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: The clear function can't be written to standard output.\n"
+    );
+}
+
+#[test]
+fn fill_runs() {
+    let mcfunction = output("fill_runs", "neg", &["--stride-z", "1", "--pad", "0"]);
+    let mcfunction = String::from_utf8(mcfunction).unwrap();
+    let lines: Vec<_> = mcfunction.lines().collect();
+    // `neg` is 0b0010_0111, followed by bytes that are all clear.
+    assert_eq!(
+        lines[..5],
+        [
+            "fill ~-2 ~-15 ~0 ~-2 ~-15 ~1 minecraft:air replace",
+            "setblock ~-2 ~-15 ~2 minecraft:redstone_wall_torch[facing=east] replace",
+            "fill ~-2 ~-15 ~3 ~-2 ~-15 ~4 minecraft:air replace",
+            "fill ~-2 ~-15 ~5 ~-2 ~-15 ~7 minecraft:redstone_wall_torch[facing=east] replace",
+            "fill ~-4 ~-15 ~0 ~-4 ~-15 ~7 minecraft:air replace",
+        ]
+    );
+    assert_eq!(lines.len(), 4 + 127);
+
+    // Bits that are apart are only filled when clear, replacing just the set bits between the
+    // ends, and are placed one by one when set.
+    let mcfunction = output("strided_runs", "neg", &["--pad", "0"]);
+    let mcfunction = String::from_utf8(mcfunction).unwrap();
+    let lines: Vec<_> = mcfunction.lines().collect();
+    let torch = "minecraft:redstone_wall_torch[facing=east]";
+    assert_eq!(
+        lines[..7],
+        [
+            format!("fill ~-2 ~-15 ~0 ~-2 ~-15 ~-2 minecraft:air replace {torch}"),
+            format!("setblock ~-2 ~-15 ~-4 {torch} replace"),
+            format!("fill ~-2 ~-15 ~-6 ~-2 ~-15 ~-8 minecraft:air replace {torch}"),
+            format!("setblock ~-2 ~-15 ~-10 {torch} replace"),
+            format!("setblock ~-2 ~-15 ~-12 {torch} replace"),
+            format!("setblock ~-2 ~-15 ~-14 {torch} replace"),
+            format!("fill ~-4 ~-15 ~0 ~-4 ~-15 ~-14 minecraft:air replace {torch}"),
+        ]
+    );
+    assert_eq!(lines.len(), 6 + 127);
+}
+
+#[test]
+fn init_datapack() {
+    let args = [
+        "--init-datapack",
+        "--namespace",
+        "cpu",
+        "--pack-format",
+        "18",
+        "--clear",
+        "-f",
+        "mcfunction",
+        "-f",
+        "bin",
+    ];
+    let written = outputs("datapack", "neg", &args);
+    assert_eq!(
+        written.keys().collect::<Vec<_>>(),
+        [
+            "data/cpu/functions/test.mcfunction",
+            "data/cpu/functions/test_clear.mcfunction",
+            "pack.mcmeta",
+            "test.bin",
+        ]
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&written["pack.mcmeta"]),
+        "{\n  \"pack\": {\n    \"pack_format\": 18,\n    \"description\": \"ROM functions in namespace cpu\"\n  }\n}\n"
+    );
+
+    let (output, _) = run("datapack_namespace", "neg", &["--namespace", "CPU"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: Namespace `CPU` is not a valid namespace, which may only contain `a-z`, `0-9`, `_`, `-` and `.`.\n"
+    );
+}
+
+#[test]
+fn default_namespace() {
+    let written = outputs("default_namespace", "neg", &["--init-datapack"]);
+    assert_eq!(
+        written.keys().collect::<Vec<_>>(),
+        ["data/redstone/functions/test.mcfunction", "pack.mcmeta"]
+    );
+}
+
+#[test]
+fn check() {
+    let args = [
+        "--check",
+        "--init-datapack",
+        "--clear",
+        "--listing",
+        "out/test.lst",
+    ];
+    assert!(outputs("check", "ldi 5\nhalt", &args).is_empty());
+
+    let (output, written) = run("check_error", "nop\nfoo", &["--check"]);
+    assert!(!output.status.success());
+    assert!(written.is_empty());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .ends_with("Error: Could not assemble `test.rasm` due to 1 error.\n"));
+}
+
+#[test]
+fn include() {
+    // Paths are relative to the file containing the `.include`.
+    let files: &[(&str, &[u8])] = &[
+        (
+            "test.rasm",
+            b".include \"lib/macros.rasm\"\ntwice\n.byte SEVEN",
+        ),
+        (
+            "lib/macros.rasm",
+            b".include \"consts.rasm\"\n.macro twice\nneg\nneg\n.endm",
+        ),
+        ("lib/consts.rasm", b".equ SEVEN 7"),
+    ];
+    let (output, written) = run_files("include", files, &["-f", "bin"]);
+    assert!(output.status.success() && output.stderr.is_empty());
+    assert_eq!(written["test.bin"][..3], [0b001_00_111, 0b001_00_111, 7]);
+
+    let with_stderr = |name, files| {
+        let (output, written) = run_files(name, files, &[]);
+        assert!(!output.status.success() && written.is_empty());
+        String::from_utf8(output.stderr).unwrap()
+    };
+    let missing = with_stderr(
+        "include_missing",
+        &[("test.rasm", b"neg\n.include \"missing.rasm\"")],
+    );
+    assert!(
+        missing.starts_with("Error: Line 2 could not include `missing.rasm`: "),
+        "{missing}"
+    );
+    assert_eq!(
+        with_stderr("include_self", &[("test.rasm", b".include \"test.rasm\"")]),
+        "Error: Line 1 includes `test.rasm` recursively.\nError: Could not assemble `test.rasm` due to 1 error.\n"
+    );
+    let cycle: &[(&str, &[u8])] = &[
+        ("test.rasm", b".include \"lib/a.rasm\""),
+        ("lib/a.rasm", b"neg\n.include \"b.rasm\""),
+        ("lib/b.rasm", b".include \"a.rasm\""),
+    ];
+    assert_eq!(
+        with_stderr("include_cycle", cycle),
+        "Error: Line 1 of `lib/b.rasm` (included from line 2 of `lib/a.rasm`, included from line 1) includes `lib/a.rasm` recursively.\nError: Could not assemble `test.rasm` due to 1 error.\n"
+    );
+    assert_eq!(
+        with_stderr("include_invalid", &[("test.rasm", b".include lib.rasm")]),
+        "Error: Line 1 has an invalid `.include` path.\nError: Could not assemble `test.rasm` due to 1 error.\n"
+    );
+
+    // Errors in an included file name it and are reported in the order of the source.
+    let errors: &[(&str, &[u8])] = &[
+        ("test.rasm", b"neg\n.include \"lib.rasm\"\nadd r5"),
+        ("lib.rasm", b"add r4"),
+    ];
+    assert_eq!(
+        with_stderr("include_errors", errors),
+        "Error: Line 1 of `lib.rasm` (included from line 2) uses `r4`, which is not one of r0-r3.\n    add r4\n        ^^\n\
+         Error: Line 3 uses `r5`, which is not one of r0-r3.\n    add r5\n        ^^\n\
+         Error: Could not assemble `test.rasm` due to 2 errors.\n"
+    );
+}
+
+#[test]
+fn watch() {
+    let output = Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+        .args(["-i", "-", "--watch"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: Standard input can't be watched, pass a file with `--input` instead.\n"
+    );
+
+    let dir = std::env::temp_dir().join(format!("redstone_assembler_watch_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("test.rasm"), ".include \"lib.rasm\"").unwrap();
+    fs::write(dir.join("lib.rasm"), "neg").unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+        .args(["-i", "test.rasm", "-f", "bin", "--watch"])
+        .current_dir(&dir)
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let wait_for = |byte: u8| {
+        for _ in 0..100 {
+            if fs::read(dir.join("test.bin")).is_ok_and(|rom| rom.first() == Some(&byte)) {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        false
+    };
+    let assembled = wait_for(0b001_00_111);
+    fs::write(dir.join("lib.rasm"), "not").unwrap();
+    let reassembled = wait_for(0b001_01_111);
+    // Editors often save by replacing the file with another.
+    fs::write(dir.join("lib.rasm.tmp"), "inc").unwrap();
+    fs::rename(dir.join("lib.rasm.tmp"), dir.join("lib.rasm")).unwrap();
+    let replaced = wait_for(0b001_10_111);
+    child.kill().unwrap();
+    child.wait().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(assembled && reassembled && replaced);
+}
+
+#[test]
+fn list_instructions() {
+    let output = Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+        .arg("--list-instructions")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Arithmetic:\n  sub   r0-r3       0b00000000\n"));
+    assert!(stdout.contains("Branch:\n  brvs              0b11000000\n"));
+    assert!(stdout.contains("\n  brnc              0b11000111\nStatus:\n"));
+    assert!(stdout.ends_with("\n  ldi   0-255       stl and sth\n"));
+}
+
+#[test]
+fn explain() {
+    let explain = |byte: &str| {
+        Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+            .args(["--explain", byte])
+            .output()
+            .unwrap()
+    };
+    assert_eq!(
+        String::from_utf8(explain("0xC4").stdout).unwrap(),
+        "0xc4 = 0b11000100: breq\n  bits 7-0  11000100  opcode\n"
+    );
+    assert_eq!(
+        String::from_utf8(explain("0b11101110").stdout).unwrap(),
+        "0xee = 0b11101110: mov r1 r3\n  bits 7-5  111       opcode\n  bits 4-3  01        source r1\n  bits 2-1  11        destination r3\n  bit 0     0         source r1\n"
+    );
+    assert_eq!(
+        String::from_utf8(explain("213").stdout).unwrap(),
+        "0xd5 = 0b11010101: ssr 0b0101\n  bits 7-4  1101      opcode\n  bits 3-0  0101      status VC\n"
+    );
+
+    let unused = explain("0x60");
+    assert_eq!(unused.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(unused.stderr).unwrap(),
+        "Error: Byte 0x60 is not used by any instruction.\n"
+    );
+}
+
+#[test]
+fn eval() {
+    let eval = |line: &str| {
+        Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+            .args(["--eval", line])
+            .output()
+            .unwrap()
+    };
+    assert_eq!(
+        String::from_utf8(eval("add r1").stdout).unwrap(),
+        "0x0c = 0b00001100: add r1\n  bits 7-5  000       opcode\n  bits 4-3  01        source r1\n  bits 2-0  100       opcode\n"
+    );
+    assert_eq!(
+        String::from_utf8(eval("ldi 0x5A").stdout).unwrap(),
+        "0x8a = 0b10001010: stl 10\n  bits 7-4  1000      opcode\n  bits 3-0  1010      immediate 10\n0x95 = 0b10010101: sth 5\n  bits 7-4  1001      opcode\n  bits 3-0  0101      immediate 5\n"
+    );
+    assert_eq!(
+        String::from_utf8(eval(".byte 0x60").stdout).unwrap(),
+        "0x60 = 0b01100000: not an instruction\n"
+    );
+
+    let invalid = eval("add r9");
+    assert_eq!(invalid.status.code(), Some(1));
+    assert!(String::from_utf8(invalid.stderr)
+        .unwrap()
+        .ends_with("\nError: Could not assemble `add r9`.\n"));
+}
+
+#[test]
+fn unreachable_warning() {
+    let source = "jmp end\nneg\nnot\n.byte 1\nend:\nneg\nhalt\n.equ X 1\ninc\n_loop:\ndec";
+    let (output, written) = run("unreachable", source, &["-f", "bin"]);
+    assert!(output.status.success());
+    assert_eq!(written.len(), 1);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Warning: Line 2 is unreachable, as it follows an unconditional jump and has no label.\n\
+         Warning: Line 9 is unreachable, as it follows an unconditional jump and has no label.\n"
+    );
+    outputs("unreachable_no_warn", source, &["--no-warn"]);
+
+    // A jump makes its target reachable, even when computed rather than a label.
+    let source = "jmp s + 4\ns:\nneg\njmp s\nneg\nsdl 11\nsdh 0\njmp\nneg";
+    outputs("unreachable_computed", source, &["--warnings-as-errors"]);
+}
+
+#[test]
+fn rom_usage() {
+    let dir = std::env::temp_dir().join(format!("redstone_assembler_usage_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("test.rasm"), "ldi 5\n.org 0x10\n.space 100").unwrap();
+    let usage = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+            .args(["-i", "test.rasm", "-f", "bin", "--force"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stderr).unwrap()
+    };
+    let summary = "Used 102/128 bytes of the ROM.\n";
+    assert_eq!(usage(&[]), summary);
+    assert_eq!(usage(&["--usage-warning", "80"]), summary);
+    assert_eq!(
+        usage(&["--usage-warning", "79"]),
+        format!(
+            "Warning: The program uses 102 of the 128 bytes of the ROM, more than 79%.\n{summary}"
+        )
+    );
+    assert_eq!(usage(&["--usage-warning", "79", "--no-warn"]), summary);
+    assert_eq!(usage(&["--quiet"]), "");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn existing_output() {
+    let dir = std::env::temp_dir().join(format!("redstone_assembler_force_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("test.rasm"), "neg").unwrap();
+    fs::write(dir.join("test.lst"), "keep").unwrap();
+    let assemble = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+            .args([
+                "-i",
+                "test.rasm",
+                "-f",
+                "bin",
+                "--listing",
+                "test.lst",
+                "--quiet",
+            ])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+    let refused = assemble(&[]);
+    assert_eq!(refused.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(refused.stderr).unwrap(),
+        "Error: `test.lst` already exists, pass `--force` to overwrite it.\n"
+    );
+    assert!(!dir.join("test.bin").exists());
+    assert_eq!(fs::read_to_string(dir.join("test.lst")).unwrap(), "keep");
+
+    assert!(assemble(&["--force"]).status.success());
+    assert_eq!(fs::read(dir.join("test.bin")).unwrap()[0], 0b001_00_111);
+    assert_ne!(fs::read_to_string(dir.join("test.lst")).unwrap(), "keep");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn warnings_as_errors() {
+    let source = "halt\nneg";
+    let (output, written) = run("warnings_as_errors", source, &["--warnings-as-errors"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(written.is_empty());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Warning: Line 2 is unreachable, as it follows an unconditional jump and has no label.\n\
+         Error: Could not assemble `test.rasm` as warnings are treated as errors.\n"
+    );
+
+    let args = ["--warnings-as-errors", "--usage-warning", "0"];
+    let (output, _) = run("usage_as_error", "neg", &args);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Warning: The program uses 1 of the 128 bytes of the ROM, more than 0%.\n\
+         Error: Could not assemble `test.rasm` as warnings are treated as errors.\n"
+    );
+    outputs("no_warnings_as_errors", "neg", &["--warnings-as-errors"]);
+}
+
+#[test]
+fn unused_labels() {
+    let source = "start:\nloop: dec\nbrne loop\n_unused:\nused: .equ X used\nhalt";
+    let (output, _) = run("unused_labels", source, &["-f", "bin"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Warning: Line 1 defines label `start`, which is never used.\n    start:\n    ^^^^^ rename it to `_start` if it is meant to be unused\n"
+    );
+}
+
+#[test]
+fn json_messages() {
+    let (output, _) = run(
+        "json_errors",
+        "add r4\nneg \"\\\tx",
+        &["--message-format", "json"],
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "{\"severity\":\"error\",\"file\":\"test.rasm\",\"line\":1,\"column\":5,\"message\":\"uses `r4`, which is not one of r0-r3\",\"help\":null}\n\
+         {\"severity\":\"error\",\"file\":\"test.rasm\",\"line\":2,\"column\":1,\"message\":\"does not contain a valid instruction `neg \\\"\\\\\\tx`\",\"help\":null}\n\
+         {\"severity\":\"error\",\"file\":null,\"line\":null,\"column\":null,\"message\":\"Could not assemble `test.rasm` due to 2 errors.\",\"help\":null}\n"
+    );
+    // The column counts the block comments before the token.
+    let (output, _) = run(
+        "json_block_comment",
+        "/* ab */ stl 99",
+        &["--message-format", "json"],
+    );
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("{\"severity\":\"error\",\"file\":\"test.rasm\",\"line\":1,\"column\":14,"));
+
+    let args = ["--message-format", "json", "--usage-warning", "0"];
+    let (output, _) = run("json_warnings", "start:\nneg", &args);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "{\"severity\":\"warning\",\"file\":\"test.rasm\",\"line\":1,\"column\":1,\"message\":\"defines label `start`, which is never used\",\"help\":\"rename it to `_start` if it is meant to be unused\"}\n\
+         {\"severity\":\"warning\",\"file\":null,\"line\":null,\"column\":null,\"message\":\"The program uses 1 of the 128 bytes of the ROM, more than 0%.\",\"help\":null}\n"
+    );
+}
+
+#[test]
+fn colored_messages() {
+    let (output, _) = run("color_error", "add r4", &["--color", "always"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "\x1b[1;31mError:\x1b[0m Line 1 uses `r4`, which is not one of r0-r3.\n    add r4\n        \x1b[1;31m^^\x1b[0m\n\
+         \x1b[1;31mError:\x1b[0m Could not assemble `test.rasm` due to 1 error.\n"
+    );
+    let (output, _) = run("color_warning", "start: neg", &["--color", "always"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "\x1b[1;33mWarning:\x1b[0m Line 1 defines label `start`, which is never used.\n    start: neg\n    \x1b[1;33m^^^^^\x1b[0m\x1b[36m rename it to `_start` if it is meant to be unused\x1b[0m\n"
+    );
+    // Standard error is not a terminal when running the tests.
+    let (output, _) = run("color_auto", "add r4", &[]);
+    assert!(!String::from_utf8(output.stderr).unwrap().contains('\x1b'));
+}
+
+#[test]
+fn stats() {
+    let source = "ldi 5\nadd r1\nadd r2\nclr r1\nbreq end\nend: halt\n.byte 1";
+    let (output, _) = run("stats", source, &["--stats", "--check", "--no-warn"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mnemonics:\n    2  add\n    2  sdh\n    2  sdl\n    1  breq\n    1  jmp\n    1  mov\n    \
+         1  sth\n    1  stl\n    1  sub\nCategories:\n    6  Immediate\n    3  Arithmetic\n    \
+         2  Branch\n    1  Move\n   12  instructions in total\n"
+    );
+
+    let (output, _) = run("stats_stdout", "nop", &["--stats", "-o", "-"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Statistics can't be printed with the output to standard output."));
+}
+
+#[test]
+fn pad() {
+    // The 124 bytes after `neg` and `halt` are a `nop` and 41 `halt`s that jump to themselves.
+    let rom = assemble("pad", "neg\nhalt");
+    assert_eq!(rom[4], 0b111_00_00_0);
+    for address in (5..128).step_by(3) {
+        assert_eq!(
+            rom[address..address + 3],
+            [0xA0 | address as u8 & 0xF, 0xB0 | address as u8 >> 4, 0xC1]
+        );
+    }
+
+    for (pad, byte) in [
+        ("0", 0x00),
+        ("0xC4", 0xC4),
+        ("NOP", 0xE0),
+        ("mov adr r2", 0xED),
+    ] {
+        let rom = output("pad_byte", "neg", &["-f", "bin", "--pad", pad]);
+        assert!(rom[1..].iter().all(|&b| b == byte), "{pad}");
+    }
+
+    let (output, _) = run("pad_ldi", "neg", &["--pad", "ldi 3"]);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("`ldi 3` is not `halt`, a byte or an instruction of one byte"));
+
+    let (disassembled, _) = run("pad_disassemble", rom, &["--disassemble"]);
+    assert_eq!(
+        String::from_utf8(disassembled.stdout).unwrap(),
+        "neg\nsdl 1\nsdh 0\njmp\n"
+    );
+}
+
+#[test]
+fn end_to_end() {
+    let dir = std::env::temp_dir().join(format!(
+        "redstone_assembler_end_to_end_{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("src/blink.rasm"), "loop: not\njmp loop\n").unwrap();
+    fs::write(dir.join("src/broken.rasm"), "not\nblink r1\n").unwrap();
+    let cli = |input: &str| {
+        Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+            .args(["-i", input, "--out-dir", "build/roms", "-f", "bin"])
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+
+    // The output directory is created and the file is named after the input.
+    let output = cli("src/blink.rasm");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stderr, b"Used 4/128 bytes of the ROM.\n");
+    let rom = fs::read(dir.join("build/roms/blink.bin")).unwrap();
+    assert_eq!(rom.len(), 128);
+    assert_eq!(
+        rom[..4],
+        [0b001_01_111, 0b1010_0000, 0b1011_0000, 0b110_00001]
+    );
+
+    let output = cli("src/broken.rasm");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.starts_with("Error: Line 2 uses unknown instruction or macro `blink`."),
+        "{stderr}"
+    );
+    assert!(stderr.ends_with("Error: Could not assemble `src/broken.rasm` due to 1 error.\n"));
+    assert!(!dir.join("build/roms/broken.bin").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn current_address() {
+    // `$` and `.` are the address of the first byte of their line, so a line's bytes all see
+    // the same address and a line that emits nothing sees the address of the next byte.
+    let source =
+        "table: .byte 1, 2, 3\n.byte $ - table, ., $\n.equ NEXT $\nnot\n.byte NEXT, $ + 1\nldi .";
+    let rom = assemble("current_address", source);
+    assert_eq!(rom[..6], [1, 2, 3, 3, 3, 3]);
+    assert_eq!(rom[6..9], [0b001_01_111, 6, 8]);
+    assert_eq!(rom[9..11], [0b1000_1001, 0b1001_0000]);
+
+    // A self-relative `.org` skips the 3 bytes after its own address.
+    let rom = assemble("current_address_org", "neg\n.org $ + 4\nnot");
+    assert_eq!(rom[5], 0b001_01_111);
+}
+
+#[test]
+fn expressions() {
+    // `*` binds tighter than `+`, `&` tighter than `^`, and `-` groups to the left.
+    let source = ".equ BASE 0x20\n.byte 1 + 2 * 3, (1 << 3) | 2, 10 - 4 - 3, 7 / 2, 6 & 3 ^ 1, BASE + 2, -2 * -3\nstl (1 << 3) - 1";
+    assert_eq!(
+        assemble("expressions", source)[..8],
+        [7, 10, 3, 3, 3, 0x22, 6, 0b100_0_0111]
+    );
+
+    assert_eq!(
+        assemble_error("expression_invalid", "stl 1 +"),
+        "Error: Line 1 has invalid expression `1 +`.\n    stl 1 +\n        ^^^\n"
+    );
+    assert_eq!(
+        assemble_error("expression_division", ".byte 1 / (2 - 2)"),
+        "Error: Line 1 divides by zero in `1 / (2 - 2)`.\n    .byte 1 / (2 - 2)\n          ^^^^^^^^^^^\n"
+    );
+    assert_eq!(
+        assemble_error("expression_overflow", ".byte 0x7FFFFFFFFFFFFFFF + 1"),
+        "Error: Line 1 overflows in `0x7FFFFFFFFFFFFFFF + 1`.\n    .byte 0x7FFFFFFFFFFFFFFF + 1\n          ^^^^^^^^^^^^^^^^^^^^^^\n"
+    );
+    let nested = |depth| format!("stl {}1{}", "(".repeat(depth), ")".repeat(depth));
+    assemble("expression_nested", &nested(63));
+    // Deeper nesting is an error rather than a stack overflow.
+    for depth in [64, 2040] {
+        let errors = assemble_error("expression_too_deep", &nested(depth));
+        assert!(
+            errors
+                .starts_with("Error: Line 1 has an expression nested more than 64 levels deep.\n"),
+            "{errors}"
+        );
+    }
+}
+
+#[test]
+fn low_high() {
+    let source = ".org 0x5B\nend: stl LOW(end)\nsth HIGH(end)\nsdl low (end + 1)\nsdh HIGH(0x1FC)\nldi HIGH(end) + 1";
+    let rom = assemble("low_high", source);
+    assert_eq!(
+        rom[0x5B..0x61],
+        [
+            0b1000_1011,
+            0b1001_0101,
+            0b1010_1100,
+            0b1011_1111,
+            0b1000_0110,
+            0b1001_0000
+        ]
+    );
+    assert_eq!(
+        assemble_error("unknown_function", "stl MID(3)"),
+        "Error: Line 1 uses unknown function `MID`, expected `LOW` or `HIGH`.\n    stl MID(3)\n        ^^^^^^\n"
+    );
+}
+
+#[test]
+fn local_labels() {
+    // Every `1b` and `1f` picks the nearest `1:` before and after it, skipping the one on its own
+    // line when looking forward.
+    let source = "1: dec\nbrne 1b\n1:\njmp 1f\n2: neg\n1: not\nbreq 1b\nbreq 2b\n1: jmp 1f\n.byte 1b\n1: halt";
+    let rom = assemble("local_labels", source);
+    let target = |address: usize| rom[address] & 0b1111 | (rom[address + 1] & 0b1111) << 4;
+    assert_eq!(
+        [1, 4, 9, 12, 15].map(target),
+        [0x00, 0x08, 0x08, 0x07, 0x13]
+    );
+    assert_eq!(rom[0x12], 0x0F);
+
+    assert_eq!(
+        assemble_error("local_label_missing", "1: jmp 1f"),
+        "Error: Line 1 uses local label `1f`, but no `1:` follows.\n    1: jmp 1f\n           ^^\n"
+    );
+    assert_eq!(
+        assemble_error("local_label_backward", "jmp 2b\n2: neg"),
+        "Error: Line 1 uses local label `2b`, but no `2:` precedes it.\n    jmp 2b\n        ^^\n"
+    );
+    // An operand ending in a character of several bytes is no local label, just invalid.
+    assert_eq!(
+        assemble_errors("local_label_unicode", "stl é\njmp ü\n.byte 1 + ß", &[]),
+        "Error: Line 1 has invalid expression `é`.\n    stl é\n        ^\n\
+         Error: Line 2 has invalid expression `ü`.\n    jmp ü\n        ^\n\
+         Error: Line 3 has invalid expression `1 + ß`.\n    .byte 1 + ß\n          ^^^^^\n"
+    );
+}
+
+#[test]
+fn macros() {
+    // A macro may be invoked any number of times, and after a label.
+    assert_eq!(
+        assemble(
+            "macros",
+            ".macro twice\nneg\nneg\n.endm\ntwice\nnot\nagain: twice\njmp again"
+        )[..6],
+        [
+            0b001_00_111,
+            0b001_00_111,
+            0b001_01_111,
+            0b001_00_111,
+            0b001_00_111,
+            0b101_0_0011
+        ]
+    );
+
+    let errors = [
+        (
+            ".macro m\nm\n.endm\nm",
+            "Error: Line 2 expands macro `m` recursively.\n",
+        ),
+        (
+            ".macro a\nb\n.endm\n.macro b\na\n.endm\na",
+            "Error: Line 5 expands macro `a` recursively.\n",
+        ),
+        (
+            ".macro m\nneg",
+            "Error: Line 1 opens macro `m` without a matching `.endm`.\n",
+        ),
+        (
+            ".macro m\n.endm\n.macro m\n.endm",
+            "Error: Line 3 redefines macro `m`.\n",
+        ),
+        (
+            ".macro neg\n.endm",
+            "Error: Line 1 defines a macro with invalid name `neg`.\n",
+        ),
+        (
+            ".macro m\n.macro n\n.endm",
+            "Error: Line 2 defines a macro inside macro `m`.\n",
+        ),
+        (
+            ".endm",
+            "Error: Line 1 has `.endm` without a matching `.macro`.\n",
+        ),
+    ];
+    for (source, error) in errors {
+        assert_eq!(assemble_error("macro_error", source), error, "{source}");
+    }
+}
+
+#[test]
+fn macro_parameters() {
+    // Arguments may be expressions, and a parameter is replaced wherever it is used.
+    let source = ".macro load lo, hi\nstl \\lo\nsth \\hi\n.endm\nload 1, 2\nload 3 + 4, 0xF";
+    assert_eq!(
+        assemble("macro_parameters", source)[..4],
+        [0b100_0_0001, 0b100_1_0010, 0b100_0_0111, 0b100_1_1111]
+    );
+
+    assert_eq!(
+        assemble_error(
+            "macro_arguments",
+            ".macro load lo, hi\nstl \\lo\n.endm\nload 1"
+        ),
+        "Error: Line 4 passes 1 arguments to macro `load`, which takes 2.\n"
+    );
+    assert_eq!(
+        assemble_error("macro_parameter_invalid", ".macro m a, 1b\n.endm"),
+        "Error: Line 1 defines macro `m` with invalid parameter `1b`.\n"
+    );
+}
+
+#[test]
+fn macro_labels() {
+    // Each expansion gets its own `loop`, which doesn't clash with the one outside the macro or
+    // rename the `loop` in the string and the `\loop` parameter.
+    let source = ".macro wait loop\nldi \\loop\nloop: dec\nbrne loop\n.ascii \"loop\"\n.endm\nwait 3\nloop: wait 5\njmp loop";
+    let rom = assemble("macro_labels", source);
+    assert_eq!(rom[..3], [0b1000_0011, 0b1001_0000, 0b001_11_111]);
+    assert_eq!(rom[3..5], [0b101_0_0010, 0b101_1_0000]);
+    assert_eq!(rom[6..10], *b"loop");
+    assert_eq!(rom[0x0D..0x0F], [0b101_0_1100, 0b101_1_0000]);
+    assert_eq!(rom[0x14..0x16], [0b101_0_1010, 0b101_1_0000]);
+
+    // The renamed labels can't clash with any label of the program, which can't name its own
+    // labels the way expansions do.
+    let source = ".macro m\nx: dec\nbrne x\n.endm\n__x_1: m\nx_2: m\njmp __x_1\njmp x_2";
+    assert_eq!(
+        assemble("macro_labels_clash", source)[..4],
+        [0b001_11_111, 0b101_0_0000, 0b101_1_0000, 0b110_00_101]
+    );
+    assert_eq!(
+        assemble_error("macro_labels_reserved", "x@1: neg"),
+        "Error: Line 1 defines label `x@1`, but only macro and `.rept` expansions may name labels with `@`.\n    x@1: neg\n    ^^^\n"
+    );
+
+    // An unused label in a macro is reported by the name it is written with.
+    let (output, _) = run(
+        "macro_labels_unused",
+        ".macro m\nx: dec\n_y: neg\n.endm\nm",
+        &["-f", "bin"],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Warning: Line 2 defines label `x`, which is never used.\n    x@1: dec\n    ^^^ rename it to `_x` if it is meant to be unused\n"
+    );
+}
+
+#[test]
+fn semicolon_separators() {
+    let source = "stl 0; sth 0; mov r0 r1 // load; move\nloop: dec;brne loop; // loop\n.ascii \"a;b\"; .byte ';'\n.macro twice; neg; neg; .endm\ntwice";
+    let rom = assemble("semicolons", source);
+    assert_eq!(
+        rom[..4],
+        [0b1000_0000, 0b1001_0000, 0b111_00_01_0, 0b001_11_111]
+    );
+    assert_eq!(
+        rom[7..13],
+        [b'a', b';', b'b', b';', 0b001_00_111, 0b001_00_111]
+    );
+
+    // The whole line is shown, with the caret under the token's column in it.
+    assert_eq!(
+        assemble_error("semicolon_error", "neg; not;add r7 // r7"),
+        "Error: Line 1 uses `r7`, which is not one of r0-r3.\n    neg; not;add r7 // r7\n                 ^^\n"
+    );
+    // A line a macro rewrote is shown as it was expanded.
+    assert_eq!(
+        assemble_error("semicolon_macro_error", ".macro m x; add \\x; .endm\nm r7"),
+        "Error: Line 1 uses `r7`, which is not one of r0-r3.\n     add r7\n         ^^\n"
+    );
+}
+
+#[test]
+fn rept() {
+    let source = ".rept 4, i\n.byte \\i * 3\n.endr\n.rept 0\nnot\n.endr\n.macro two\n.rept 2\nloop: dec\nbrne loop\n.endr\n.endm\ntwo";
+    let rom = assemble("rept", source);
+    assert_eq!(rom[..4], [0, 3, 6, 9]);
+    // Every repetition has its own `loop`.
+    assert_eq!(
+        rom[4..8],
+        [0b001_11_111, 0b101_0_0100, 0b101_1_0000, 0b110_00_101]
+    );
+    assert_eq!(
+        rom[8..12],
+        [0b001_11_111, 0b101_0_1000, 0b101_1_0000, 0b110_00_101]
+    );
+
+    // The count may use the constants defined before it.
+    assert_eq!(
+        assemble(
+            "rept_constant",
+            ".equ N 3\n.set M N - 1\n.rept N * M\n.byte 1\n.endr\n.byte 2"
+        )[..7],
+        [1, 1, 1, 1, 1, 1, 2]
+    );
+    assert_eq!(
+        output(
+            "rept_define",
+            ".rept COUNT\nneg\n.endr\n.byte 2",
+            &["-f", "bin", "-D", "COUNT=2"]
+        )[..3],
+        [0b001_00_111, 0b001_00_111, 2]
+    );
+    assert_eq!(
+        assemble_error("rept_label", "start:\n.rept start\n.endr"),
+        "Error: Line 2 uses undefined symbol `start`.\n"
+    );
+    assert_eq!(
+        assemble_error("rept_negative", ".rept -1\nneg\n.endr"),
+        "Error: Line 1 has `.rept` count -1, which is not in 0..=128.\n"
+    );
+    assert_eq!(
+        assemble_error("rept_unclosed", ".rept 2\nneg"),
+        "Error: Line 1 opens `.rept` without a matching `.endr`.\n"
+    );
+    assert_eq!(
+        assemble_error(
+            "rept_nested",
+            ".rept 128\n.rept 128\n.rept 128\n.set X 1\n.endr\n.endr\n.endr"
+        ),
+        "Error: Line 3 expands to more than 65536 lines.\n"
+    );
+    let nested = |depth| {
+        format!(
+            "{}neg\n{}",
+            ".rept 1\n".repeat(depth),
+            ".endr\n".repeat(depth)
+        )
+    };
+    assert_eq!(assemble("rept_deep", &nested(64))[0], 0b001_00_111);
+    assert_eq!(
+        assemble_error("rept_too_deep", &nested(65)),
+        "Error: Line 65 nests includes, macros and `.rept` blocks more than 64 levels deep.\n"
+    );
+}
+
+#[test]
+fn conditional_assembly() {
+    let program = |debug: &str| {
+        format!("{debug}\n.ifdef DEBUG\nneg\n.if DEBUG >= 2\nnot\n.else\ninc\n.endif\n.else\ndec\n.if 0\n.byte UNDEFINED\n.endif\n.endif\n.byte 7")
+    };
+    assert_eq!(assemble("debug_off", &program(""))[..2], [0b001_11_111, 7]);
+    assert_eq!(
+        assemble("debug_on", &program(".equ DEBUG 1"))[..3],
+        [0b001_00_111, 0b001_10_111, 7]
+    );
+    assert_eq!(
+        assemble("debug_verbose", &program(".set DEBUG 2"))[..3],
+        [0b001_00_111, 0b001_01_111, 7]
+    );
+
+    assert_eq!(
+        assemble_error("unterminated_if", "neg\n.if 1\n.ifdef X\n.endif"),
+        "Error: Line 2 opens `.if` without a matching `.endif`.\n"
+    );
+    assert_eq!(
+        assemble_error("endif_without_if", ".endif"),
+        "Error: Line 1 has `.endif` without a matching `.if`.\n    .endif\n    ^^^^^^\n"
+    );
+
+    // Conditions are decided before macros, includes and `.rept` blocks are expanded.
+    let macros = ".ifdef DEBUG\n.macro trace\nneg\n.endm\n.else\n.macro trace\n.endm\n.endif\ntrace\n.byte 7";
+    assert_eq!(assemble("conditional_macro", macros)[0], 7);
+    assert_eq!(
+        output(
+            "conditional_macro_debug",
+            macros,
+            &["-f", "bin", "-D", "DEBUG"]
+        )[..2],
+        [0b001_00_111, 7]
+    );
+    let include = ".ifdef DEBUG\n.include \"debug.inc\"\n.endif\nneg";
+    assert_eq!(assemble("conditional_include", include)[0], 0b001_00_111);
+    assert!(
+        assemble_errors("conditional_include_debug", include, &["-D", "DEBUG"])
+            .starts_with("Error: Line 2 could not include `debug.inc`: ")
+    );
+    assert_eq!(
+        assemble("conditional_rept", ".if 0\n.rept 1000\n.endr\n.endif\nneg")[0],
+        0b001_00_111
+    );
+    assert_eq!(
+        assemble(
+            "rept_conditional",
+            ".rept 3, i\n.if \\i == 1\nneg\n.else\n.byte \\i\n.endif\n.endr"
+        )[..3],
+        [0, 0b001_00_111, 2]
+    );
+    assert_eq!(
+        assemble_error("if_label", "start:\n.if start\n.endif"),
+        "Error: Line 2 uses undefined symbol `start`.\n    .if start\n        ^^^^^\n"
+    );
+}
+
+#[test]
+fn defines() {
+    let source = ".ifdef DEBUG\nneg\n.endif\n.byte VERSION";
+    let rom = |args: &[&str]| output("defines", source, &[&["-f", "bin"], args].concat());
+    assert_eq!(rom(&["-D", "VERSION=3"])[0], 3);
+    assert_eq!(
+        rom(&["-D", "DEBUG", "--define", "VERSION=-0x1"])[..2],
+        [0b001_00_111, 0xFF]
+    );
+
+    assert_eq!(
+        assemble_errors("define_redefined", ".equ VERSION 2", &["-D", "VERSION"]),
+        "Error: Line 1 redefines constant `VERSION`, which is already defined with `-D`.\n    .equ VERSION 2\n         ^^^^^^^\n"
+    );
+    let (output, _) = run("define_invalid", "neg", &["-D", "sp=1"]);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("`sp` is not a valid constant name"));
+}
+
+#[test]
+fn preview() {
+    let layout = [
+        "--size-x",
+        "4",
+        "--stride-x",
+        "1",
+        "--offset-x",
+        "0",
+        "--size-y",
+        "32",
+        "--stride-y",
+        "1",
+        "--offset-y",
+        "0",
+        "--stride-z",
+        "1",
+    ];
+    let source = ".byte 0x81, 0x7E, 0xF0, 0x0F";
+    let (output, written) = run(
+        "preview",
+        source,
+        &[&layout[..], &["--preview", "--pad", "0"]].concat(),
+    );
+    assert!(output.status.success());
+    assert!(written.is_empty());
+    let preview = String::from_utf8(output.stdout).unwrap();
+    assert!(preview.starts_with("y ~31:\n....\n"));
+    assert!(preview.ends_with("\ny ~0:\n#.#.\n.##.\n.##.\n.##.\n.#.#\n.#.#\n.#.#\n#..#\n"));
+    assert_eq!(preview.lines().count(), 32 * 10 - 1);
+}
+
+#[test]
+fn patch_functions() {
+    let old = output("patch_old", "neg\nhalt", &["-f", "bin"]);
+    let path =
+        std::env::temp_dir().join(format!("redstone_assembler_old_{}.bin", std::process::id()));
+    fs::write(&path, &old).unwrap();
+    // `neg` and `not` only differ in bit 3, so the rest of the ROM is left alone.
+    let written = outputs(
+        "patch",
+        "not\nhalt",
+        &["--patch-from", path.to_str().unwrap()],
+    );
+    assert_eq!(
+        written.keys().collect::<Vec<_>>(),
+        [
+            "test.mcfunction",
+            "test_patch.mcfunction",
+            "test_unpatch.mcfunction"
+        ]
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&written["test_patch.mcfunction"]),
+        "setblock ~-2 ~-15 ~-8 minecraft:redstone_wall_torch[facing=east] replace\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&written["test_unpatch.mcfunction"]),
+        "setblock ~-2 ~-15 ~-8 minecraft:air replace\n"
+    );
+
+    fs::write(&path, &old[..4]).unwrap();
+    let (output, _) = run(
+        "patch_short",
+        "not",
+        &["--patch-from", path.to_str().unwrap()],
+    );
+    fs::remove_file(&path).unwrap();
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "Error: `{}` has 4 bytes, but a ROM as written by the `bin` format has 128.\n",
+            path.display()
+        )
+    );
+}
+
+#[test]
+fn verify_function() {
+    let written = outputs("verify", "neg\nhalt", &["--verify"]);
+    let program = String::from_utf8_lossy(&written["test.mcfunction"]);
+    let verify = String::from_utf8_lossy(&written["test_verify.mcfunction"]);
+    let lines = verify.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3 + 3 * 128);
+    assert_eq!(
+        lines[4],
+        "execute if score #byte rom_verify matches 0 run tellraw @s \"Byte 0x00 does not hold 0x27.\""
+    );
+    // Every bit is checked for the block the function places there, where a `fill` places the
+    // bits every -2 along z.
+    let placed = program
+        .lines()
+        .flat_map(|line| match line.split(' ').collect::<Vec<_>>()[..] {
+            ["setblock", x, y, z, block, _] => vec![format!("{x} {y} {z} {block}")],
+            ["fill", x, y, start, _, _, end, block, ..] => {
+                let z = |z: &str| z[1..].parse::<i32>().unwrap();
+                (z(end)..=z(start))
+                    .rev()
+                    .step_by(2)
+                    .map(|z| format!("{x} {y} ~{z} {block}"))
+                    .collect()
+            }
+            _ => panic!("unexpected command `{line}`"),
+        })
+        .collect::<Vec<_>>();
+    let checked = lines
+        .iter()
+        .filter_map(|line| {
+            line.strip_prefix("execute store success score #byte rom_verify if block ")
+        })
+        .flat_map(|checks| checks.split(" if block "))
+        .collect::<Vec<_>>();
+    assert_eq!(checked, placed);
+
+    let (output, _) = run("verify_stdout", "neg", &["--verify", "-o", "-"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: The verify function can't be written to standard output.\n"
+    );
+}
+
+#[test]
+fn config_file() {
+    let dir =
+        std::env::temp_dir().join(format!("redstone_assembler_config_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("test.rasm"), ".byte VERSION").unwrap();
+    fs::write(
+        dir.join("redstone-assembler.toml"),
+        "# Shared by the project\nout-dir = \"build\"\nformat = [\"bin\", \"mcfunction\"]\n\
+         offset_z = -5 # along the wall\nquiet = true\ndefine = ['VERSION=3']\n",
+    )
+    .unwrap();
+    fs::write(dir.join("invalid.toml"), "quiet = true\nnamespace\n").unwrap();
+    let cli = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_redstone_assembler"))
+            .args(["-i", "test.rasm", "--force"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+
+    // The config file takes precedence over the built-in defaults.
+    let output = cli(&[]);
+    assert!(output.status.success() && output.stderr.is_empty());
+    assert_eq!(fs::read(dir.join("build/test.bin")).unwrap()[0], 3);
+    let function = fs::read_to_string(dir.join("build/test.mcfunction")).unwrap();
+    assert!(function.starts_with("fill ~-2 ~-15 ~-5 "), "{function}");
+
+    // Options on the command line take precedence over the config file.
+    let output = cli(&["-f", "mcfunction", "--offset-z", "1", "-D", "VERSION=5"]);
+    assert!(output.status.success());
+    let function = fs::read_to_string(dir.join("build/test.mcfunction")).unwrap();
+    // The first five bits of 0b0000_0101 are cleared with one `fill`.
+    assert!(
+        function.starts_with("fill ~-2 ~-15 ~1 ~-2 ~-15 ~-7 "),
+        "{function}"
+    );
+    let bits = function.lines().skip(1).take(3);
+    let bits = bits.map(|line| line.starts_with("setblock") && line.contains("torch"));
+    assert!(bits.eq([true, false, true]));
+
+    let output = cli(&["--config", "invalid.toml"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with(
+        "error: `invalid.toml` line 2 is not an `option = value` line, such as `namespace = \"cpu\"`.\n"
+    ));
+    let output = cli(&["--config", "missing.toml"]);
+    assert!(!output.status.success());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,17 @@
+#include <stdint.h>
+
+#define PROGRAM_LEN 128
+
+static const uint8_t program[PROGRAM_LEN] = {
+    0x8b, 0x9a, 0xa2, 0xb0, 0xc1, 0x00, 0xe0, 0xa7, 0xb0, 0xc1, 0xaa, 0xb0,
+    0xc1, 0xad, 0xb0, 0xc1, 0xa0, 0xb1, 0xc1, 0xa3, 0xb1, 0xc1, 0xa6, 0xb1,
+    0xc1, 0xa9, 0xb1, 0xc1, 0xac, 0xb1, 0xc1, 0xaf, 0xb1, 0xc1, 0xa2, 0xb2,
+    0xc1, 0xa5, 0xb2, 0xc1, 0xa8, 0xb2, 0xc1, 0xab, 0xb2, 0xc1, 0xae, 0xb2,
+    0xc1, 0xa1, 0xb3, 0xc1, 0xa4, 0xb3, 0xc1, 0xa7, 0xb3, 0xc1, 0xaa, 0xb3,
+    0xc1, 0xad, 0xb3, 0xc1, 0xa0, 0xb4, 0xc1, 0xa3, 0xb4, 0xc1, 0xa6, 0xb4,
+    0xc1, 0xa9, 0xb4, 0xc1, 0xac, 0xb4, 0xc1, 0xaf, 0xb4, 0xc1, 0xa2, 0xb5,
+    0xc1, 0xa5, 0xb5, 0xc1, 0xa8, 0xb5, 0xc1, 0xab, 0xb5, 0xc1, 0xae, 0xb5,
+    0xc1, 0xa1, 0xb6, 0xc1, 0xa4, 0xb6, 0xc1, 0xa7, 0xb6, 0xc1, 0xaa, 0xb6,
+    0xc1, 0xad, 0xb6, 0xc1, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x2c, 0x20, 0x72,
+    0x65, 0x64, 0x73, 0x74, 0x6f, 0x6e, 0x65, 0x21,
+};
@@ -0,0 +1,759 @@
+setblock ~-2 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-2 ~-15 ~-2 ~-2 ~-15 ~-6 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-2 ~-15 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-2 ~-15 ~-10 minecraft:air replace
+setblock ~-2 ~-15 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-2 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-4 ~-15 ~-2 ~-4 ~-15 ~-4 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-4 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-15 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-15 ~-10 minecraft:air replace
+setblock ~-4 ~-15 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-15 ~-14 minecraft:air replace
+setblock ~-6 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-15 ~-2 minecraft:air replace
+setblock ~-6 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-6 ~-15 ~-6 ~-6 ~-15 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-6 ~-15 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-15 ~-14 minecraft:air replace
+setblock ~-8 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-8 ~-15 ~-2 minecraft:air replace
+setblock ~-8 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-8 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-8 ~-15 ~-8 ~-8 ~-15 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-10 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-10 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-10 ~-15 ~-4 ~-10 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-10 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-12 ~-15 ~0 ~-12 ~-15 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-14 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-14 ~-15 ~-6 ~-14 ~-15 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-16 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-15 ~-2 minecraft:air replace
+setblock ~-16 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-16 ~-15 ~-6 ~-16 ~-15 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-16 ~-15 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-15 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-15 ~-2 minecraft:air replace
+setblock ~-18 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-18 ~-15 ~-8 ~-18 ~-15 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-20 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-20 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-20 ~-15 ~-4 ~-20 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-20 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-15 ~-2 minecraft:air replace
+setblock ~-22 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-15 ~-6 minecraft:air replace
+setblock ~-22 ~-15 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-15 ~-10 minecraft:air replace
+setblock ~-22 ~-15 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-15 ~-14 minecraft:air replace
+setblock ~-24 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-15 ~-2 minecraft:air replace
+setblock ~-24 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-24 ~-15 ~-8 ~-24 ~-15 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-26 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-26 ~-15 ~-4 ~-26 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-26 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-15 ~-2 minecraft:air replace
+setblock ~-28 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-15 ~-6 minecraft:air replace
+setblock ~-28 ~-15 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-15 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-15 ~-12 minecraft:air replace
+setblock ~-28 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-15 ~-2 minecraft:air replace
+setblock ~-30 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-30 ~-15 ~-8 ~-30 ~-15 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-32 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-32 ~-15 ~-4 ~-32 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-32 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-34 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-34 ~-15 ~-2 minecraft:air replace
+setblock ~-34 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-34 ~-15 ~-6 ~-34 ~-15 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-36 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-36 ~-15 ~-2 minecraft:air replace
+setblock ~-36 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-36 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-36 ~-15 ~-8 ~-36 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-36 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-38 ~-15 ~-4 ~-38 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-38 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-15 ~-2 minecraft:air replace
+setblock ~-40 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-40 ~-15 ~-6 ~-40 ~-15 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-40 ~-15 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-15 ~-2 minecraft:air replace
+setblock ~-42 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-42 ~-15 ~-8 ~-42 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-42 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-44 ~-15 ~-4 ~-44 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-44 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-15 ~-2 minecraft:air replace
+setblock ~-46 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-46 ~-15 ~-6 ~-46 ~-15 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-46 ~-15 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-15 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-15 ~-14 minecraft:air replace
+setblock ~-48 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-15 ~-2 minecraft:air replace
+setblock ~-48 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-48 ~-15 ~-8 ~-48 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-48 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-50 ~-15 ~-4 ~-50 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-50 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-15 ~-2 minecraft:air replace
+setblock ~-52 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-15 ~-6 minecraft:air replace
+setblock ~-52 ~-15 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-52 ~-15 ~-10 ~-52 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-52 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-15 ~-2 minecraft:air replace
+setblock ~-54 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-54 ~-15 ~-8 ~-54 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-54 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-56 ~-15 ~-4 ~-56 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-56 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-15 ~-2 minecraft:air replace
+setblock ~-58 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-15 ~-6 minecraft:air replace
+setblock ~-58 ~-15 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-15 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-58 ~-15 ~-12 ~-58 ~-15 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-60 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-15 ~-2 minecraft:air replace
+setblock ~-60 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-15 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-60 ~-15 ~-8 ~-60 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-60 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-15 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-62 ~-15 ~-4 ~-62 ~-15 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-62 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-15 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-15 ~-2 minecraft:air replace
+setblock ~-64 ~-15 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-15 ~-6 minecraft:air replace
+setblock ~-64 ~-15 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-15 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-15 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-15 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-2 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-2 ~-11 ~-2 minecraft:air replace
+setblock ~-2 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-2 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-2 ~-11 ~-8 ~-2 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-2 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-4 ~-11 ~-4 ~-4 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-4 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-11 ~-2 minecraft:air replace
+setblock ~-6 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-6 ~-11 ~-6 ~-6 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-6 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-11 ~-14 minecraft:air replace
+setblock ~-8 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-8 ~-11 ~-2 minecraft:air replace
+setblock ~-8 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-8 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-8 ~-11 ~-8 ~-8 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-8 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-8 ~-11 ~-14 minecraft:air replace
+setblock ~-10 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-10 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-10 ~-11 ~-4 ~-10 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-10 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-12 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-12 ~-11 ~-2 minecraft:air replace
+setblock ~-12 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-12 ~-11 ~-6 ~-12 ~-11 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-12 ~-11 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-12 ~-11 ~-12 minecraft:air replace
+setblock ~-12 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-11 ~-2 minecraft:air replace
+setblock ~-14 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-14 ~-11 ~-8 ~-14 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-14 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-11 ~-14 minecraft:air replace
+setblock ~-16 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-16 ~-11 ~-4 ~-16 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-16 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-11 ~-2 minecraft:air replace
+setblock ~-18 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-11 ~-6 minecraft:air replace
+setblock ~-18 ~-11 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-18 ~-11 ~-10 ~-18 ~-11 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-20 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-20 ~-11 ~-2 minecraft:air replace
+setblock ~-20 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-20 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-20 ~-11 ~-8 ~-20 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-20 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-20 ~-11 ~-14 minecraft:air replace
+setblock ~-22 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-22 ~-11 ~-4 ~-22 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-22 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-11 ~-2 minecraft:air replace
+setblock ~-24 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-11 ~-6 minecraft:air replace
+setblock ~-24 ~-11 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-11 ~-10 minecraft:air replace
+setblock ~-24 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-11 ~-2 minecraft:air replace
+setblock ~-26 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-26 ~-11 ~-8 ~-26 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-26 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-11 ~-14 minecraft:air replace
+setblock ~-28 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-28 ~-11 ~-4 ~-28 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-28 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-11 ~-2 minecraft:air replace
+setblock ~-30 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-11 ~-6 minecraft:air replace
+setblock ~-30 ~-11 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-11 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-11 ~-14 minecraft:air replace
+setblock ~-32 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-11 ~-2 minecraft:air replace
+setblock ~-32 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-32 ~-11 ~-8 ~-32 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-32 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-11 ~-14 minecraft:air replace
+setblock ~-34 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-34 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-34 ~-11 ~-4 ~-34 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-34 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-36 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-36 ~-11 ~-2 minecraft:air replace
+setblock ~-36 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-36 ~-11 ~-6 ~-36 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-36 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-11 ~-2 minecraft:air replace
+setblock ~-38 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-38 ~-11 ~-8 ~-38 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-38 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-40 ~-11 ~-4 ~-40 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-40 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-11 ~-2 minecraft:air replace
+setblock ~-42 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-42 ~-11 ~-6 ~-42 ~-11 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-42 ~-11 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-42 ~-11 ~-12 ~-42 ~-11 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-44 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-11 ~-2 minecraft:air replace
+setblock ~-44 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-44 ~-11 ~-8 ~-44 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-44 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-46 ~-11 ~-4 ~-46 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-46 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-11 ~-2 minecraft:air replace
+setblock ~-48 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-48 ~-11 ~-6 ~-48 ~-11 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-48 ~-11 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-11 ~-2 minecraft:air replace
+setblock ~-50 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-50 ~-11 ~-8 ~-50 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-50 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-52 ~-11 ~-4 ~-52 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-52 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-11 ~-2 minecraft:air replace
+setblock ~-54 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-11 ~-6 minecraft:air replace
+setblock ~-54 ~-11 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-11 ~-10 minecraft:air replace
+setblock ~-54 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-11 ~-14 minecraft:air replace
+setblock ~-56 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-11 ~-2 minecraft:air replace
+setblock ~-56 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-56 ~-11 ~-8 ~-56 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-56 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-58 ~-11 ~-4 ~-58 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-58 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-11 ~-2 minecraft:air replace
+setblock ~-60 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-11 ~-6 minecraft:air replace
+setblock ~-60 ~-11 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-11 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-11 ~-12 minecraft:air replace
+setblock ~-60 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-11 ~-2 minecraft:air replace
+setblock ~-62 ~-11 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-11 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-62 ~-11 ~-8 ~-62 ~-11 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-62 ~-11 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-11 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-11 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-64 ~-11 ~-4 ~-64 ~-11 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-64 ~-11 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-2 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-2 ~-7 ~-2 minecraft:air replace
+setblock ~-2 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-2 ~-7 ~-6 ~-2 ~-7 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-4 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-7 ~-2 minecraft:air replace
+setblock ~-4 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-7 ~-8 minecraft:air replace
+setblock ~-4 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-4 ~-7 ~-12 ~-4 ~-7 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-6 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-6 ~-7 ~-4 ~-6 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-6 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-8 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-8 ~-7 ~-2 minecraft:air replace
+setblock ~-8 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-8 ~-7 ~-6 ~-8 ~-7 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-8 ~-7 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-8 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-10 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-10 ~-7 ~-2 minecraft:air replace
+setblock ~-10 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-10 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-10 ~-7 ~-8 minecraft:air replace
+setblock ~-10 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-10 ~-7 ~-12 ~-10 ~-7 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-12 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-12 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-12 ~-7 ~-4 ~-12 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-12 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-7 ~-2 minecraft:air replace
+setblock ~-14 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-14 ~-7 ~-6 ~-14 ~-7 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-14 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-7 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-7 ~-14 minecraft:air replace
+setblock ~-16 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-7 ~-2 minecraft:air replace
+setblock ~-16 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-7 ~-8 minecraft:air replace
+setblock ~-16 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-16 ~-7 ~-12 ~-16 ~-7 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-18 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-18 ~-7 ~-4 ~-18 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-18 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-20 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-20 ~-7 ~-2 minecraft:air replace
+setblock ~-20 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-20 ~-7 ~-6 minecraft:air replace
+setblock ~-20 ~-7 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-20 ~-7 ~-10 ~-20 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-20 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-7 ~-2 minecraft:air replace
+setblock ~-22 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-7 ~-8 minecraft:air replace
+setblock ~-22 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-22 ~-7 ~-12 ~-22 ~-7 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-24 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-24 ~-7 ~-4 ~-24 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-24 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-7 ~-2 minecraft:air replace
+setblock ~-26 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-7 ~-6 minecraft:air replace
+setblock ~-26 ~-7 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-26 ~-7 ~-12 ~-26 ~-7 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-28 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-7 ~-2 minecraft:air replace
+setblock ~-28 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-7 ~-8 minecraft:air replace
+setblock ~-28 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-28 ~-7 ~-12 ~-28 ~-7 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-30 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-30 ~-7 ~-4 ~-30 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-30 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-7 ~-2 minecraft:air replace
+setblock ~-32 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-7 ~-6 minecraft:air replace
+setblock ~-32 ~-7 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-7 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-34 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-34 ~-7 ~-2 minecraft:air replace
+setblock ~-34 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-34 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-34 ~-7 ~-8 minecraft:air replace
+setblock ~-34 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-34 ~-7 ~-12 ~-34 ~-7 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-36 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-36 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-36 ~-7 ~-4 ~-36 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-36 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-7 ~-2 minecraft:air replace
+setblock ~-38 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-38 ~-7 ~-6 ~-38 ~-7 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-38 ~-7 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-7 ~-14 minecraft:air replace
+setblock ~-40 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-7 ~-2 minecraft:air replace
+setblock ~-40 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-7 ~-8 minecraft:air replace
+setblock ~-40 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-7 ~-12 minecraft:air replace
+setblock ~-40 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-42 ~-7 ~-4 ~-42 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-42 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-7 ~-2 minecraft:air replace
+setblock ~-44 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-44 ~-7 ~-6 ~-44 ~-7 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-44 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-7 ~-12 minecraft:air replace
+setblock ~-44 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-7 ~-2 minecraft:air replace
+setblock ~-46 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-7 ~-8 minecraft:air replace
+setblock ~-46 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-46 ~-7 ~-12 minecraft:air replace
+setblock ~-46 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-48 ~-7 ~-4 ~-48 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-48 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-7 ~-2 minecraft:air replace
+setblock ~-50 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-7 ~-6 minecraft:air replace
+setblock ~-50 ~-7 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-50 ~-7 ~-10 ~-50 ~-7 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-52 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-7 ~-2 minecraft:air replace
+setblock ~-52 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-7 ~-8 minecraft:air replace
+setblock ~-52 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-7 ~-12 minecraft:air replace
+setblock ~-52 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-54 ~-7 ~-4 ~-54 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-54 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-7 ~-2 minecraft:air replace
+setblock ~-56 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-7 ~-6 minecraft:air replace
+setblock ~-56 ~-7 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-7 ~-10 minecraft:air replace
+setblock ~-56 ~-7 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-7 ~-2 minecraft:air replace
+setblock ~-58 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-7 ~-8 minecraft:air replace
+setblock ~-58 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-7 ~-12 minecraft:air replace
+setblock ~-58 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-7 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-60 ~-7 ~-4 ~-60 ~-7 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-60 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-7 ~-2 minecraft:air replace
+setblock ~-62 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-7 ~-6 minecraft:air replace
+setblock ~-62 ~-7 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-7 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-7 ~-14 minecraft:air replace
+setblock ~-64 ~-7 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-7 ~-2 minecraft:air replace
+setblock ~-64 ~-7 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-7 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-7 ~-8 minecraft:air replace
+setblock ~-64 ~-7 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-64 ~-7 ~-12 minecraft:air replace
+setblock ~-64 ~-7 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-2 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-2 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-2 ~-3 ~-4 ~-2 ~-3 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-2 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-4 ~-3 ~-2 minecraft:air replace
+setblock ~-4 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-4 ~-3 ~-6 ~-4 ~-3 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-4 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-3 ~-2 minecraft:air replace
+setblock ~-6 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-3 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-3 ~-8 minecraft:air replace
+setblock ~-6 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-6 ~-3 ~-14 minecraft:air replace
+setblock ~-8 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-8 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-8 ~-3 ~-4 ~-8 ~-3 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-8 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-10 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-10 ~-3 ~-2 minecraft:air replace
+setblock ~-10 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-10 ~-3 ~-6 ~-10 ~-3 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-10 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-10 ~-3 ~-12 ~-10 ~-3 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-12 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-12 ~-3 ~-2 minecraft:air replace
+setblock ~-12 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-12 ~-3 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-12 ~-3 ~-8 minecraft:air replace
+setblock ~-12 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-12 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-12 ~-3 ~-14 minecraft:air replace
+setblock ~-14 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-14 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-14 ~-3 ~-4 ~-14 ~-3 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-14 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-3 ~-2 minecraft:air replace
+setblock ~-16 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-16 ~-3 ~-6 ~-16 ~-3 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-16 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-16 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-3 ~-2 minecraft:air replace
+setblock ~-18 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-3 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-3 ~-8 minecraft:air replace
+setblock ~-18 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-18 ~-3 ~-14 minecraft:air replace
+setblock ~-20 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-20 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-20 ~-3 ~-4 ~-20 ~-3 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-20 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-3 ~-2 minecraft:air replace
+setblock ~-22 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-3 ~-6 minecraft:air replace
+setblock ~-22 ~-3 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-3 ~-10 minecraft:air replace
+setblock ~-22 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-22 ~-3 ~-14 minecraft:air replace
+setblock ~-24 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-3 ~-2 minecraft:air replace
+setblock ~-24 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-3 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-3 ~-8 minecraft:air replace
+setblock ~-24 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-24 ~-3 ~-14 minecraft:air replace
+setblock ~-26 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-26 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-26 ~-3 ~-4 ~-26 ~-3 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-26 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-3 ~-2 minecraft:air replace
+setblock ~-28 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-3 ~-6 minecraft:air replace
+setblock ~-28 ~-3 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-28 ~-3 ~-12 minecraft:air replace
+setblock ~-28 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-3 ~-2 minecraft:air replace
+setblock ~-30 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-3 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-3 ~-8 minecraft:air replace
+setblock ~-30 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-30 ~-3 ~-14 minecraft:air replace
+setblock ~-32 ~-3 ~0 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-32 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-32 ~-3 ~-4 ~-32 ~-3 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-32 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-34 ~-3 ~0 minecraft:air replace
+setblock ~-34 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-34 ~-3 ~-4 ~-34 ~-3 ~-6 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-34 ~-3 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-34 ~-3 ~-10 ~-34 ~-3 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-36 ~-3 ~0 minecraft:air replace
+setblock ~-36 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-36 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-36 ~-3 ~-6 ~-36 ~-3 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-36 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-36 ~-3 ~-12 minecraft:air replace
+setblock ~-36 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-3 ~0 minecraft:air replace
+setblock ~-38 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-3 ~-6 minecraft:air replace
+setblock ~-38 ~-3 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-38 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-38 ~-3 ~-12 ~-38 ~-3 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-40 ~-3 ~0 minecraft:air replace
+setblock ~-40 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-3 ~-6 minecraft:air replace
+setblock ~-40 ~-3 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-40 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-40 ~-3 ~-12 ~-40 ~-3 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-42 ~-3 ~0 minecraft:air replace
+setblock ~-42 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-3 ~-6 minecraft:air replace
+setblock ~-42 ~-3 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-42 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-44 ~-3 ~0 ~-44 ~-3 ~-2 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-44 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-3 ~-6 minecraft:air replace
+setblock ~-44 ~-3 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-44 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-44 ~-3 ~-12 ~-44 ~-3 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+fill ~-46 ~-3 ~0 ~-46 ~-3 ~-2 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-46 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-46 ~-3 ~-6 ~-46 ~-3 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-48 ~-3 ~0 minecraft:air replace
+setblock ~-48 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-3 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-48 ~-3 ~-8 ~-48 ~-3 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-48 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-48 ~-3 ~-14 minecraft:air replace
+setblock ~-50 ~-3 ~0 minecraft:air replace
+setblock ~-50 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-50 ~-3 ~-6 ~-50 ~-3 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-50 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-50 ~-3 ~-12 minecraft:air replace
+setblock ~-50 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-3 ~0 minecraft:air replace
+setblock ~-52 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-52 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-52 ~-3 ~-6 ~-52 ~-3 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-52 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-52 ~-3 ~-12 ~-52 ~-3 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-54 ~-3 ~0 minecraft:air replace
+setblock ~-54 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-3 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-54 ~-3 ~-8 ~-54 ~-3 ~-10 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-54 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-54 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-3 ~0 minecraft:air replace
+setblock ~-56 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-3 ~-6 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-56 ~-3 ~-8 minecraft:air replace
+setblock ~-56 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-56 ~-3 ~-12 ~-56 ~-3 ~-14 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-58 ~-3 ~0 minecraft:air replace
+setblock ~-58 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-3 ~-6 minecraft:air replace
+setblock ~-58 ~-3 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-58 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-3 ~0 minecraft:air replace
+setblock ~-60 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-3 ~-6 minecraft:air replace
+setblock ~-60 ~-3 ~-8 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-3 ~-12 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-60 ~-3 ~-14 minecraft:air replace
+setblock ~-62 ~-3 ~0 minecraft:air replace
+setblock ~-62 ~-3 ~-2 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-62 ~-3 ~-6 ~-62 ~-3 ~-8 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-62 ~-3 ~-10 minecraft:redstone_wall_torch[facing=east] replace
+setblock ~-62 ~-3 ~-12 minecraft:air replace
+setblock ~-62 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-64 ~-3 ~0 ~-64 ~-3 ~-2 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-64 ~-3 ~-4 minecraft:redstone_wall_torch[facing=east] replace
+fill ~-64 ~-3 ~-6 ~-64 ~-3 ~-12 minecraft:air replace minecraft:redstone_wall_torch[facing=east]
+setblock ~-64 ~-3 ~-14 minecraft:redstone_wall_torch[facing=east] replace
@@ -0,0 +1,238 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use redstone_assembler::{
+    assemble, encode_branch, source_files, Instruction, Register, Source, ROM_BYTES,
+};
+
+#[test]
+fn assemble_rom() {
+    let rom = assemble("neg\nhalt").unwrap();
+    assert_eq!(rom.len(), ROM_BYTES);
+    assert_eq!(Instruction::decode(rom[0]), Some(Instruction::Neg));
+    assert_eq!(rom[1..4], encode_branch(Instruction::Jmp, 1));
+}
+
+#[test]
+fn assemble_error_location() {
+    let e = assemble("neg\n  add r1, \tfoo").unwrap_err();
+    assert_eq!((e.line, e.column), (2, Some(3)));
+    assert_eq!(e.file, None);
+    assert_eq!(
+        e.to_string().lines().next(),
+        Some("Line 2 does not contain a valid instruction `add r1, \tfoo`.")
+    );
+    // The column counts from the start of a line holding several instructions.
+    let e = assemble("neg; add r9").unwrap_err();
+    assert_eq!((e.line, e.column), (1, Some(10)));
+}
+
+#[test]
+fn instruction_encoding() {
+    for byte in 0..=u8::MAX {
+        if let Some(instruction) = Instruction::decode(byte) {
+            assert_eq!(instruction.encode(), byte, "{instruction}");
+            assert_eq!(
+                assemble(&instruction.to_string()).unwrap()[0],
+                byte,
+                "{instruction}"
+            );
+        }
+    }
+    let mov = Instruction::Mov(Source::Adr, Register::R2);
+    assert_eq!(mov.to_string(), "mov adr r2");
+    assert_eq!(mov.encode(), 0b111_01_10_1);
+}
+
+/// Every instruction with every combination of its operands.
+fn all_instructions() -> Vec<Instruction> {
+    use Instruction::*;
+    let registers = [Register::R0, Register::R1, Register::R2, Register::R3];
+    let sources = [
+        Source::R0,
+        Source::Pc,
+        Source::R1,
+        Source::Adr,
+        Source::R2,
+        Source::Sp,
+        Source::R3,
+        Source::Sr,
+    ];
+    let mut instructions = vec![
+        Neg, Not, Inc, Dec, Brvs, Jmp, Brcs, Brcc, Breq, Brne, Brns, Brnc,
+    ];
+    for instruction in [Sub, Sbc, Cmp, Cpc, Add, Adc, Mul, Div, And, Or, Xor] {
+        instructions.extend(registers.map(instruction));
+    }
+    for instruction in [Lsl, Rol, Lsr, Ror] {
+        instructions.extend((1..=4).map(instruction));
+    }
+    for instruction in [Lds, Sts, Stl, Sth, Sdl, Sdh, Ssr] {
+        instructions.extend((0..16).map(instruction));
+    }
+    for src in sources {
+        instructions.extend(registers.map(|dst| Mov(src, dst)));
+    }
+    instructions
+}
+
+#[test]
+fn instruction_round_trip() {
+    let instructions = all_instructions();
+    for &instruction in &instructions {
+        assert_eq!(
+            Instruction::decode(instruction.encode()),
+            Some(instruction),
+            "{instruction}"
+        );
+        assert_eq!(
+            assemble(&instruction.to_string()).unwrap()[0],
+            instruction.encode(),
+            "{instruction}"
+        );
+    }
+    // With every instruction decoding back to itself, no two share an encoding, so the
+    // instructions and the decodable bytes correspond one to one.
+    let decodable = (0..=u8::MAX)
+        .filter(|&byte| Instruction::decode(byte).is_some())
+        .count();
+    assert_eq!(instructions.len(), decodable);
+}
+
+#[test]
+fn included_source_files() {
+    let dir = std::env::temp_dir().join(format!("redstone_assembler_lib_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("lib.rasm"), ".include \"missing.rasm\"").unwrap();
+    let path = dir.join("test.rasm");
+    let files = source_files(&path, ".include \"lib.rasm\"\n.include \"lib.rasm\"", &[]);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        files,
+        [path, dir.join("lib.rasm"), dir.join("missing.rasm")]
+    );
+}
+
+#[test]
+fn deeply_nested_expression() {
+    // Test threads have a smaller stack than the main thread, which this must not overflow.
+    let source = format!("stl {}1{}", "(".repeat(2040), ")".repeat(2040));
+    assert!(assemble(&source)
+        .unwrap_err()
+        .message
+        .contains("nested more than 64 levels deep"));
+}
+
+#[test]
+fn deeply_nested_rept() {
+    let source = format!("{}neg\n{}", ".rept 1\n".repeat(290), ".endr\n".repeat(290));
+    assert!(assemble(&source)
+        .unwrap_err()
+        .message
+        .contains("more than 64 levels deep"));
+}
+
+/// Pieces of source the random programs are made of, chosen to reach deep into the parser.
+const FRAGMENTS: &[&str] = &[
+    "add",
+    "mov",
+    "lsl",
+    "ssr",
+    "ldi",
+    "halt",
+    "breq",
+    "jmp",
+    "nop",
+    "call",
+    "r0",
+    "r3",
+    "sp",
+    "pc",
+    "adr",
+    "label",
+    "label:",
+    "1:",
+    "1b",
+    "1f",
+    ".org",
+    ".byte",
+    ".fill",
+    ".space",
+    ".align",
+    ".ascii",
+    ".asciz",
+    ".equ",
+    ".set",
+    ".macro",
+    ".endm",
+    ".rept",
+    ".if",
+    ".ifdef",
+    ".else",
+    ".endif",
+    ".endr",
+    ".include",
+    "\\x",
+    "0x",
+    "0b",
+    "0",
+    "1",
+    "4",
+    "15",
+    "255",
+    "-1",
+    "9223372036854775807",
+    "-9223372036854775808",
+    "+",
+    "-",
+    "*",
+    "/",
+    "%",
+    "<<",
+    ">>",
+    "&",
+    "|",
+    "^",
+    "==",
+    "<",
+    "~",
+    "$",
+    "LOW",
+    "(",
+    ")",
+    ",",
+    "\"",
+    "'",
+    "'a'",
+    "\"a\\n\"",
+    "//",
+    "/*",
+    "*/",
+    "NVZC",
+    "1010",
+    " ",
+    " ",
+    " ",
+    ";",
+    "\n",
+    "\n",
+];
+
+#[test]
+fn random_programs_dont_panic() {
+    // A fixed xorshift seed keeps failures reproducible.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut random = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for _ in 0..20_000 {
+        let length = random() % 32;
+        let source: String = (0..length)
+            .map(|_| FRAGMENTS[(random() % FRAGMENTS.len() as u64) as usize])
+            .collect();
+        let result = std::panic::catch_unwind(|| assemble(&source));
+        assert!(result.is_ok(), "panicked on {source:?}");
+    }
+}